@@ -1,6 +1,7 @@
 from __future__ import annotations
 from dataclasses import dataclass
-from typing import Iterator, Tuple
+from multiprocessing import Pool
+from typing import Iterable, Iterator, List, Tuple
 import random
 
 # Board is 8x8, squares numbered 0..63, A1=0 (LSB) to H8=63 (MSB).
@@ -27,14 +28,41 @@ NOT_FILE_H = ~FILE_H & 0xFFFFFFFFFFFFFFFF
 EDGE_MASK = 0xFF818181818181FF
 CORNER_MASK = (1 << 0) | (1 << 7) | (1 << 56) | (1 << 63)
 
-# Precompute directional masks to block wrapping
+
+class KernelError(ValueError):
+    """Malformed engine input: off-board squares, bad masks, over-cap empties.
+
+    A ValueError, so existing handlers still catch it; an illegal but well-formed
+    move stays a plain ValueError.
+    """
+
+# Smaller boards use the A1 corner of the same layout (a 6x6 board is A1-F6). Squares
+# outside the board never hold discs and are never legal, so every ray stops at the
+# board's edge just as at the 8x8 one, and the shift masks below need no change.
+BOARD_SIZES = (6, 8)
+
+
+def _board_mask(size: int) -> int:
+    return sum(((1 << size) - 1) << (8 * r) for r in range(size))
+
+
+def _corners(size: int) -> int:
+    n = size - 1
+    return (1 << 0) | (1 << n) | (1 << (8 * n)) | (1 << (8 * n + n))
+
+
+BOARD_MASKS = {n: _board_mask(n) for n in BOARD_SIZES}
+CORNER_MASKS = {n: _corners(n) for n in BOARD_SIZES}
+
+# Precompute directional masks to block wrapping. They are applied after the shift,
+# so they clear the file a disc would wrap onto (eastward moves can't land on file A).
 DIR_MASKS = {
-    1: NOT_FILE_H,
-    -1: NOT_FILE_A,
-    7: NOT_FILE_A,
-    -7: NOT_FILE_H,
-    9: NOT_FILE_H,
-    -9: NOT_FILE_A,
+    1: NOT_FILE_A,
+    -1: NOT_FILE_H,
+    7: NOT_FILE_H,
+    -7: NOT_FILE_A,
+    9: NOT_FILE_A,
+    -9: NOT_FILE_H,
     8: 0xFFFFFFFFFFFFFFFF,
     -8: 0xFFFFFFFFFFFFFFFF,
 }
@@ -43,12 +71,29 @@ DIR_MASKS = {
 random.seed(2025)
 ZOBRIST = [[random.getrandbits(64) for _ in range(64)] for _ in range(2)]
 ZOBRIST_BLACK_TO_MOVE = random.getrandbits(64)
+# XOR for a disc changing colour on a square
+ZOBRIST_FLIP = [ZOBRIST[0][i] ^ ZOBRIST[1][i] for i in range(64)]
 
 
 def popcount(x: int) -> int:
     return x.bit_count()
 
 
+def popcount_batch(masks: Iterable[int]) -> List[int]:
+    """popcount over many masks; accepts any iterable of ints, e.g. a uint64 array."""
+    return [int(m).bit_count() for m in masks]
+
+
+def bits(mask: int) -> List[int]:
+    """Indices of the set bits in `mask`, lowest first."""
+    out = []
+    while mask:
+        low = mask & -mask
+        out.append(low.bit_length() - 1)
+        mask ^= low
+    return out
+
+
 def shift(bb: int, d: int) -> int:
     if d > 0:
         return (bb << d) & 0xFFFFFFFFFFFFFFFF
@@ -56,9 +101,26 @@ def shift(bb: int, d: int) -> int:
         return (bb >> (-d)) & 0xFFFFFFFFFFFFFFFF
 
 
-def legal_moves(me: int, opp: int) -> int:
+def shift_dir(bb: int, d: int) -> int:
+    """Move every disc one step in direction `d` (one of DIRS), dropping any that would wrap."""
+    return shift(bb, d) & DIR_MASKS[d]
+
+
+def count_empties(black: int, white: int, size: int = 8) -> int:
+    return size * size - popcount(black | white)
+
+
+def adjacent(bb: int) -> int:
+    """Squares a king step away from any square in `bb` (may include `bb` itself)."""
+    out = 0
+    for d in DIRS:
+        out |= shift_dir(bb, d)
+    return out
+
+
+def legal_moves(me: int, opp: int, size: int = 8) -> int:
     """Return bitmask of legal moves for side with discs `me` against `opp`."""
-    empty = ~(me | opp) & 0xFFFFFFFFFFFFFFFF
+    empty = ~(me | opp) & BOARD_MASKS[size]
     moves = 0
     for d in DIRS:
         mask = DIR_MASKS[d]
@@ -89,25 +151,120 @@ def legal_moves(me: int, opp: int) -> int:
     return moves
 
 
+def _ray(sq: int, d: int) -> int:
+    ray = 0
+    bb = shift_dir(1 << sq, d)
+    while bb:
+        ray |= bb
+        bb = shift_dir(bb, d)
+    return ray
+
+
+# RAYS[sq] = ((direction, squares from sq to the board edge in that direction), ...)
+RAYS: Tuple[Tuple[Tuple[int, int], ...], ...] = tuple(
+    tuple((d, _ray(sq, d)) for d in DIRS if _ray(sq, d)) for sq in range(64)
+)
+
+
+def both_legal_moves(black: int, white: int, size: int = 8) -> Tuple[int, int]:
+    """Legal move masks for Black and White, from one pass over the directions."""
+    empty = ~(black | white) & BOARD_MASKS[size]
+    b_moves = w_moves = 0
+    for d in DIRS:
+        mask = DIR_MASKS[d]
+        xb = shift(black, d) & white & mask
+        xw = shift(white, d) & black & mask
+        acc_b = acc_w = 0
+        while xb or xw:
+            acc_b |= xb
+            acc_w |= xw
+            xb = shift(xb, d) & white & mask
+            xw = shift(xw, d) & black & mask
+        b_moves |= shift(acc_b, d) & empty & mask
+        w_moves |= shift(acc_w, d) & empty & mask
+    return b_moves, w_moves
+
+
 def flips_for_move(me: int, opp: int, move: int) -> int:
     """Return bitboard of discs to flip if we play `move` (single-bit int set) for `me`."""
+    if not 0 < move < 1 << 64 or move & (move - 1):
+        raise KernelError(f"Move must be a single on-board bit, got {move:#x}")
+    flips = 0
+    for d, ray in RAYS[move.bit_length() - 1]:
+        # the first square along the ray that isn't an opponent disc ends the run
+        stop = ray & ~opp
+        if d > 0:
+            first = stop & -stop
+            run = ray & (first - 1)
+        else:
+            first = 1 << (stop.bit_length() - 1) if stop else 0
+            run = ray & ~((first << 1) - 1)
+        if first & me:
+            flips |= run
+    return flips
+
+
+def flips_fill(me: int, opp: int, move: int) -> int:
+    """Same as flips_for_move, by dumb7fill: a fixed six shifts per direction, no data-dependent loop."""
     flips = 0
     for d in DIRS:
         mask = DIR_MASKS[d]
-        x = 0
-        y = shift(move, d) & opp & mask
-        while y:
-            x |= y
-            y = shift(y, d) & opp & mask
-        end = shift(x, d) & me & mask
-        if end:
-            flips |= x
+        run = shift(move, d) & mask & opp
+        for _ in range(5):
+            run |= shift(run, d) & mask & opp
+        if shift(run, d) & mask & me:
+            flips |= run
     return flips
 
 
-def play_move(me: int, opp: int, sq: int) -> Tuple[int, int]:
+def is_legal_move(me: int, opp: int, sq: int, size: int = 8) -> bool:
+    """Whether `me` may play `sq`, walking only that square's rays."""
+    if sq < 0 or not (BOARD_MASKS[size] >> sq) & 1 or ((me | opp) >> sq) & 1:
+        return False
+    for d, ray in RAYS[sq]:
+        stop = ray & ~opp
+        if d > 0:
+            first = stop & -stop
+            run = ray & (first - 1)
+        else:
+            first = 1 << (stop.bit_length() - 1) if stop else 0
+            run = ray & ~((first << 1) - 1)
+        if first & me and run:
+            return True
+    return False
+
+
+def legal_squares(me: int, opp: int, size: int = 8) -> List[int]:
+    """Sorted square indices of the legal moves for `me`."""
+    return bits(legal_moves(me, opp, size))
+
+
+def legal_count(me: int, opp: int, size: int = 8) -> int:
+    """Number of legal moves for `me`, without listing them."""
+    return popcount(legal_moves(me, opp, size))
+
+
+def all_flips(me: int, opp: int, size: int = 8) -> List[Tuple[int, int]]:
+    """(square, flip mask) for every legal move of `me`, in square order."""
+    moves = legal_moves(me, opp, size)
+    out = []
+    while moves:
+        low = moves & -moves
+        out.append((low.bit_length() - 1, flips_for_move(me, opp, low)))
+        moves ^= low
+    return out
+
+
+def flip_counts(me: int, opp: int, size: int = 8) -> List[Tuple[int, int]]:
+    """(square, discs flipped) for every legal move of `me`, in square order."""
+    return [(sq, popcount(flips)) for sq, flips in all_flips(me, opp, size)]
+
+
+def play_move(me: int, opp: int, sq: int, size: int = 8) -> Tuple[int, int]:
+    if sq < 0 or not (BOARD_MASKS[size] >> sq) & 1:
+        raise KernelError(f"Square out of range: {sq}")
     move = 1 << sq
-    flips = flips_for_move(me, opp, move)
+    flips = flips_for_move(me, opp, move) if not (me | opp) & move else 0
     if flips == 0:
         raise ValueError("Illegal move")
     me2 = me ^ (flips | move)
@@ -115,57 +272,374 @@ def play_move(me: int, opp: int, sq: int) -> Tuple[int, int]:
     return me2, opp2
 
 
+def is_wipeout(black: int, white: int) -> bool:
+    """True when one colour has no discs left, which ends the game at once."""
+    return not black or not white
+
+
+def is_game_over(black: int, white: int, size: int = 8) -> bool:
+    """True when the board is full or neither side has a legal move."""
+    if (black | white) == BOARD_MASKS[size] or is_wipeout(black, white):
+        return True
+    return legal_moves(black, white, size) == 0 and legal_moves(white, black, size) == 0
+
+
+def disc_counts(black: int, white: int) -> Tuple[int, int]:
+    return popcount(black), popcount(white)
+
+
+def disc_diff(black: int, white: int) -> int:
+    """Black's disc count minus White's, empties not counted."""
+    return popcount(black) - popcount(white)
+
+
+def final_margin(me: int, opp: int, size: int = 8) -> int:
+    """Disc margin for `me` at game end, with empty squares awarded to the winner."""
+    m, o = disc_counts(me, opp)
+    empties = size * size - m - o
+    if m > o:
+        return m - o + empties
+    if m < o:
+        return m - o - empties
+    return 0
+
+
+def final_score(black: int, white: int, size: int = 8) -> int:
+    """Final black-minus-white score of a finished game."""
+    return final_margin(black, white, size)
+
+
+def _center(size: int) -> Tuple[int, int]:
+    # (white, black) start discs: White on the a1-h8 diagonal, as on the standard board
+    c = size // 2
+    white = (1 << (8 * (c - 1) + c - 1)) | (1 << (8 * c + c))
+    black = (1 << (8 * (c - 1) + c)) | (1 << (8 * c + c - 1))
+    return white, black
+
+
+def validate_board(black: int, white: int, strict: bool = False, size: int = 8) -> None:
+    """Raise KernelError for overlapping or out-of-range masks.
+
+    With `strict`, also require the four centre squares to be occupied, as they are
+    in any position reachable from the start.
+    """
+    if size not in BOARD_MASKS:
+        raise KernelError(f"Unsupported board size {size}")
+    if black < 0 or white < 0 or (black | white) >> 64:
+        raise KernelError("Board masks must be 64-bit unsigned")
+    if (black | white) & ~BOARD_MASKS[size]:
+        raise KernelError(f"Discs outside the {size}x{size} board")
+    if black & white:
+        raise KernelError(f"Overlapping discs: {black & white:#018x}")
+    center = sum(_center(size))
+    if strict and (black | white) & center != center:
+        raise KernelError("Centre squares must be occupied")
+
+
+def zobrist(black: int, white: int, stm: int) -> int:
+    """64-bit Zobrist key, as used by Position.hash64() and the Searcher TT."""
+    h = ZOBRIST_BLACK_TO_MOVE if stm == 0 else 0
+    for i in bits(black):
+        h ^= ZOBRIST[0][i]
+    for i in bits(white):
+        h ^= ZOBRIST[1][i]
+    return h
+
+
 @dataclass(frozen=True)
 class Position:
     black: int
     white: int
     stm: int  # 0=Black,1=White
+    size: int = 8
+
+    def __post_init__(self):
+        validate_board(self.black, self.white, size=self.size)
+        if self.stm not in (0, 1):
+            raise KernelError(f"Side to move must be 0 or 1, got {self.stm}")
+
+    def __repr__(self) -> str:
+        side = "Black" if self.stm == 0 else "White"
+        size = "" if self.size == 8 else f", size={self.size}"
+        return f"Position(black={self.black:#018x}, white={self.white:#018x}, stm={side}{size})"
 
     @staticmethod
-    def initial() -> "Position":
-        black = (1 << 28) | (1 << 35)  # E4, D5 in 0-index A1=0 -> D5=27? We ensure standard: center
-        white = (1 << 27) | (1 << 36)
-        return Position(black=black, white=white, stm=0)
+    def initial(size: int = 8) -> "Position":
+        white, black = _center(size)  # 8x8: White D4/E5, Black E4/D5
+        return Position(black=black, white=white, stm=0, size=size)
 
     def me_opp(self) -> Tuple[int, int]:
         return (self.black, self.white) if self.stm == 0 else (self.white, self.black)
 
     def legal_mask(self) -> int:
         me, opp = self.me_opp()
-        return legal_moves(me, opp)
+        return legal_moves(me, opp, self.size)
+
+    def legal_squares(self) -> List[int]:
+        me, opp = self.me_opp()
+        return legal_squares(me, opp, self.size)
+
+    def is_legal(self, sq: int) -> bool:
+        me, opp = self.me_opp()
+        return is_legal_move(me, opp, sq, self.size)
 
     def pass_move(self) -> "Position":
-        return Position(self.black, self.white, 1 - self.stm)
+        return Position(self.black, self.white, 1 - self.stm, self.size)
 
     def apply(self, sq: int) -> "Position":
         me, opp = self.me_opp()
-        me2, opp2 = play_move(me, opp, sq)
+        me2, opp2 = play_move(me, opp, sq, self.size)
         if self.stm == 0:
-            return Position(me2, opp2, 1)
+            return Position(me2, opp2, 1, self.size)
         else:
-            return Position(opp2, me2, 1)
+            return Position(opp2, me2, 0, self.size)
 
     def terminal(self) -> bool:
-        if self.legal_mask() != 0:
-            return False
-        if Position(self.black, self.white, 1 - self.stm).legal_mask() != 0:
-            return False
-        return True
+        return is_game_over(self.black, self.white, self.size)
 
     def score_disc_diff(self) -> int:
-        b = popcount(self.black)
-        w = popcount(self.white)
-        return b - w  # +ve means Black ahead
+        return disc_diff(self.black, self.white)  # +ve means Black ahead
 
     def hash64(self) -> int:
-        h = 0
-        for i in range(64):
-            if (self.black >> i) & 1:
-                h ^= ZOBRIST[0][i]
-            if (self.white >> i) & 1:
-                h ^= ZOBRIST[1][i]
-        if self.stm == 0:
-            h ^= ZOBRIST_BLACK_TO_MOVE
-        return h
+        return zobrist(self.black, self.white, self.stm)
+
+
+def move_result(pos: Position, sq: int) -> Tuple[int, int, int]:
+    """(flips, new black, new white) for the side to move playing `sq`, from one flip computation."""
+    if sq < 0 or not (BOARD_MASKS[pos.size] >> sq) & 1:
+        raise KernelError(f"Square out of range: {sq}")
+    me, opp = pos.me_opp()
+    move = 1 << sq
+    flips = flips_for_move(me, opp, move) if not (me | opp) & move else 0
+    if flips == 0:
+        raise ValueError("Illegal move")
+    if pos.stm == 0:
+        return flips, pos.black | flips | move, pos.white ^ flips
+    return flips, pos.black ^ flips, pos.white | flips | move
+
+
+def flipped_squares(pos: Position, sq: int) -> List[int]:
+    """Squares the side to move would flip by playing `sq`, nearest first along each direction in DIRS."""
+    flips, _, _ = move_result(pos, sq)
+    out = []
+    for d, ray in RAYS[sq]:
+        run = bits(flips & ray)
+        out.extend(run if d > 0 else reversed(run))
+    return out
+
+
+def flip_by_direction(pos: Position, sq: int) -> List[Tuple[int, int]]:
+    """(direction, discs flipped along it) for each of DIRS if the side to move played `sq`; 0 where nothing flips."""
+    if sq < 0 or not (BOARD_MASKS[pos.size] >> sq) & 1:
+        raise KernelError(f"Square out of range: {sq}")
+    me, opp = pos.me_opp()
+    if ((me | opp) >> sq) & 1:
+        raise ValueError("Square is occupied")
+    rays = dict(RAYS[sq])
+    return [(d, flips_for_move(me, opp & rays[d], 1 << sq) if d in rays else 0) for d in DIRS]
+
+
+PASS = 64  # square value meaning "pass", as in Searcher results
+
+
+def apply_move(pos: Position, sq: int) -> Position:
+    """Play `sq` and give the turn to whoever moves next.
+
+    If the opponent has no reply but the mover does, the opponent's forced pass is
+    applied too, so the returned stm is always the side that actually plays next.
+    `sq` may be PASS when the side to move has no legal move and the game isn't over.
+    Raises ValueError for an illegal move.
+    """
+    if sq == PASS:
+        if pos.legal_mask():
+            raise ValueError("Cannot pass with legal moves available")
+        nxt = pos.pass_move()
+        if not nxt.legal_mask():
+            raise ValueError("Cannot pass: the game is over")
+        return nxt
+    nxt = pos.apply(sq)
+    if nxt.legal_mask() == 0 and nxt.pass_move().legal_mask() != 0:
+        return nxt.pass_move()
+    return nxt
+
+
+def move_record(pos: Position, sq: int) -> Tuple[int, int]:
+    """(square, flip mask) for the side to move playing `sq`: all undo_move needs to take it back.
+
+    A PASS records no flips; it is only accepted when the side to move has no legal move.
+    """
+    if sq == PASS:
+        if pos.legal_mask():
+            raise ValueError("Cannot pass with legal moves available")
+        return PASS, 0
+    flips, _, _ = move_result(pos, sq)
+    return sq, flips
+
+
+def undo_move(pos: Position, sq: int, flips: int) -> Position:
+    """Take back a move_record(prev, sq) move, given pos == prev.apply(sq) (or prev.pass_move() for PASS).
+
+    Forced passes added by apply_move are separate moves here: undo them first.
+    """
+    if sq == PASS:
+        if flips:
+            raise ValueError("A pass flips nothing")
+        return pos.pass_move()
+    if sq < 0 or not (BOARD_MASKS[pos.size] >> sq) & 1:
+        raise KernelError(f"Square out of range: {sq}")
+    move = 1 << sq
+    mover = pos.white if pos.stm == 0 else pos.black
+    if not flips or (flips | move) & ~mover or flips & move:
+        raise ValueError("Move record does not match the position")
+    mover ^= flips | move
+    if pos.stm == 0:
+        return Position(pos.black | flips, mover, 1, pos.size)
+    return Position(mover, pos.white | flips, 0, pos.size)
+
+
+def legal_mask_after(pos: Position, sq: int) -> Tuple[int, int]:
+    """(legal mask, side to move) after apply_move(pos, sq); the mask is 0 once the game is over."""
+    nxt = apply_move(pos, sq)
+    return nxt.legal_mask(), nxt.stm
+
+
+def apply_move_hashed(pos: Position, sq: int, prev_hash: int) -> Tuple[Position, int]:
+    """apply_move plus the result's hash64(), updated incrementally from `prev_hash`."""
+    nxt = apply_move(pos, sq)
+    if sq == PASS:
+        return nxt, prev_hash ^ ZOBRIST_BLACK_TO_MOVE
+    own_before = pos.black if pos.stm == 0 else pos.white
+    own_after = nxt.black if pos.stm == 0 else nxt.white
+    flips = (own_before ^ own_after) & ~(1 << sq)
+    h = prev_hash ^ ZOBRIST[pos.stm][sq]
+    while flips:
+        low = flips & -flips
+        h ^= ZOBRIST_FLIP[low.bit_length() - 1]
+        flips ^= low
+    if nxt.stm != pos.stm:
+        h ^= ZOBRIST_BLACK_TO_MOVE
+    return nxt, h
+
+
+def _apply_row(row: Tuple[int, int, int, int]) -> Tuple[int, int, int, bool]:
+    b, w, stm, sq = (int(v) for v in row)
+    try:
+        nxt = apply_move(Position(b, w, stm), sq)
+    except ValueError:
+        return b, w, stm, False
+    return nxt.black, nxt.white, nxt.stm, True
+
+
+def apply_move_batch(black: Iterable[int], white: Iterable[int], stm: Iterable[int], sq: Iterable[int],
+                     workers: int = 1) -> Tuple[List[int], List[int], List[int], List[bool]]:
+    """apply_move over many (position, square) pairs: (blacks, whites, stms, legal), one entry per pair.
+
+    An illegal pair comes back unchanged with legal False. Inputs may be any
+    equal-length sequences of ints; workers > 1 splits the pairs across processes.
+    """
+    rows = list(zip(black, white, stm, sq, strict=True))
+    if workers <= 1:
+        out = [_apply_row(r) for r in rows]
+    else:
+        with Pool(processes=workers) as pool:
+            out = pool.map(_apply_row, rows, chunksize=max(1, len(rows) // (4 * workers)))
+    return [r[0] for r in out], [r[1] for r in out], [r[2] for r in out], [r[3] for r in out]
+
+
+def pack_position(pos: Position) -> int:
+    """One int token for a position: black in bits 0-63, white in 64-127, stm in bit 128."""
+    return pos.black | (pos.white << 64) | (pos.stm << 128)
+
+
+def unpack_position(token: int) -> Position:
+    if token < 0 or token >> 129:
+        raise ValueError(f"Bad position token {token:#x}")
+    return Position(token & 0xFFFFFFFFFFFFFFFF, (token >> 64) & 0xFFFFFFFFFFFFFFFF, token >> 128)
+
+
+def random_position(empties: int, seed: int, size: int = 8) -> Position:
+    """Reproducible position reached by random legal play from the start with `empties` empty squares.
+
+    Games that finish early are replayed with the same generator, so any count from
+    0 to size*size - 4 is reachable.
+    """
+    start = Position.initial(size)
+    if not 0 <= empties <= count_empties(start.black, start.white, size):
+        raise ValueError(f"Empties out of range: {empties}")
+    rng = random.Random(seed)
+    while True:
+        pos = start
+        while count_empties(pos.black, pos.white, size) > empties and not pos.terminal():
+            moves = pos.legal_squares()
+            pos = pos.apply(rng.choice(moves)) if moves else pos.pass_move()
+        if count_empties(pos.black, pos.white, size) == empties:
+            return pos
+
+
+def parse_board(s: str) -> Position:
+    """Parse 64 chars of X (Black), O (White) or '.' in square order, plus an optional X/O side to move."""
+    s = "".join(s.split())
+    if len(s) == 65:
+        s, side = s[:64], s[64]
+        if side not in "XO":
+            raise ValueError(f"Bad side-to-move suffix {side!r}")
+        stm = 0 if side == "X" else 1
+    elif len(s) == 64:
+        stm = 0
+    else:
+        raise ValueError(f"Board string must have 64 squares, got {len(s)}")
+    black = white = 0
+    for i, ch in enumerate(s):
+        if ch == "X":
+            black |= 1 << i
+        elif ch == "O":
+            white |= 1 << i
+        elif ch != ".":
+            raise ValueError(f"Bad square character {ch!r} at {i}")
+    return Position(black, white, stm)
+
+
+def render_board(pos: Position) -> str:
+    """Grid with file/rank labels, laid out like the UI (A1 top-left)."""
+    lines = ["  " + " ".join("ABCDEFGH"[:pos.size])]
+    for r in range(pos.size):
+        row = []
+        for c in range(pos.size):
+            i = r * 8 + c
+            if (pos.black >> i) & 1:
+                row.append("X")
+            elif (pos.white >> i) & 1:
+                row.append("O")
+            else:
+                row.append(".")
+        lines.append(f"{r+1} " + " ".join(row))
+    lines.append(f"{'X' if pos.stm == 0 else 'O'} to move")
+    return "\n".join(lines)
+
+
+def perft(pos: Position, depth: int) -> int:
+    """Count leaf nodes `depth` plies below `pos`.
+
+    A forced pass consumes a ply like any move; a finished game (double pass) is a leaf.
+    """
+    me, opp = pos.me_opp()
+    return _perft(me, opp, depth, pos.size)
+
+
+def _perft(me: int, opp: int, depth: int, size: int = 8) -> int:
+    if depth == 0:
+        return 1
+    moves = legal_moves(me, opp, size)
+    if not moves:
+        if not legal_moves(opp, me, size):
+            return 1
+        return _perft(opp, me, depth - 1, size)
+    nodes = 0
+    while moves:
+        move = moves & -moves
+        moves ^= move
+        flips = flips_for_move(me, opp, move)
+        nodes += _perft(opp ^ flips, me | flips | move, depth - 1, size)
+    return nodes
 
 
@@ -1,6 +1,14 @@
 from __future__ import annotations
 from dataclasses import dataclass
-from .bitboard import popcount, CORNER_MASK, EDGE_MASK, Position, legal_moves
+from multiprocessing import Pool
+from typing import Iterable, List, Optional, Sequence, Tuple
+from .bitboard import (
+    popcount, adjacent, all_flips, apply_move, bits, both_legal_moves, count_empties, final_margin, BOARD_MASKS,
+    CORNER_MASK, CORNER_MASKS, EDGE_MASK, FILE_A, Position, legal_moves,
+)
+from .stability import stability_diff
+from .parity import odd_empties
+from .symmetry import transform
 
 # Phase-aware linear evaluation with common Othello features.
 
@@ -12,79 +20,337 @@ class EvalWeights:
     corners: int = 800
     corner_adj_penalty: int = -120
     frontier: int = -20
+    stability: int = 60
     disc_diff: int = 2
 
 DEFAULT_WEIGHTS = EvalWeights()
 
+# Evaluation units per disc of final margin, shared with the searches so a finished
+# game and a static evaluation can be compared directly.
+DISC_SCALE = 100
 
-def potential_mobility(me: int, opp: int) -> int:
+
+def check_eval_size(pos: Position):
+    # the weights and the phase blend are tuned for the 8x8 board; the features take a size
+    if pos.size != 8:
+        raise ValueError(f"The static evaluation needs the 8x8 board, got a {pos.size}x{pos.size} board")
+
+
+def mobility_diff(me: int, opp: int, size: int = 8) -> int:
+    """Legal-move count for `me` minus that for `opp`."""
+    mine, theirs = both_legal_moves(me, opp, size)
+    return popcount(mine) - popcount(theirs)
+
+
+def _safe_moves(me: int, opp: int, size: int = 8) -> int:
+    # moves after which the opponent has no corner to take
+    safe = 0
+    for sq, flips in all_flips(me, opp, size):
+        move = 1 << sq
+        if not legal_moves(opp ^ flips, me | flips | move, size) & CORNER_MASKS[size]:
+            safe += 1
+    return safe
+
+
+def safe_mobility(pos: Position) -> int:
+    """Like mobility_diff from the side to move's view, ignoring moves that hand the opponent a corner."""
+    me, opp = pos.me_opp()
+    return _safe_moves(me, opp, pos.size) - _safe_moves(opp, me, pos.size)
+
+
+def potential_mobility(me: int, opp: int, size: int = 8) -> int:
     # Number of empty squares adjacent to opponent discs
-    empty = ~(me | opp) & 0xFFFFFFFFFFFFFFFF
-    adj = 0
-    # Adjacent using king moves
-    adj |= ((opp << 8) | (opp >> 8)) & 0xFFFFFFFFFFFFFFFF
-    left = (opp & 0xFEFEFEFEFEFEFEFE) << 1
-    right = (opp & 0x7F7F7F7F7F7F7F7F) >> 1
-    adj |= left | right
-    adj |= ((left << 8) | (right << 8) | (left >> 8) | (right >> 8)) & 0xFFFFFFFFFFFFFFFF
-    return popcount(adj & empty)
-
-
-def frontier_discs(me: int, opp: int) -> int:
-    empty = ~(me | opp) & 0xFFFFFFFFFFFFFFFF
+    empty = ~(me | opp) & BOARD_MASKS[size]
+    return popcount(adjacent(opp) & empty)
+
+
+def potential_mobility_split(black: int, white: int, size: int = 8) -> Tuple[int, int]:
+    """Potential mobility of Black and White: empty squares next to the other colour's discs."""
+    empty = ~(black | white) & BOARD_MASKS[size]
+    return popcount(adjacent(white) & empty), popcount(adjacent(black) & empty)
+
+
+def frontier_discs(me: int, opp: int, size: int = 8) -> int:
     # A disc is frontier if adjacent to any empty
-    adj_empty = 0
-    adj_empty |= ((empty << 8) | (empty >> 8)) & 0xFFFFFFFFFFFFFFFF
-    left = (empty & 0xFEFEFEFEFEFEFEFE) << 1
-    right = (empty & 0x7F7F7F7F7F7F7F7F) >> 1
-    adj_empty |= left | right
-    adj_empty |= ((left << 8) | (right << 8) | (left >> 8) | (right >> 8)) & 0xFFFFFFFFFFFFFFFF
-    return popcount(me & adj_empty)
-
-
-def corner_score(me: int, opp: int) -> int:
-    me_c = popcount(me & CORNER_MASK)
-    opp_c = popcount(opp & CORNER_MASK)
+    empty = ~(me | opp) & BOARD_MASKS[size]
+    return popcount(me & adjacent(empty))
+
+
+def frontier_counts(black: int, white: int, size: int = 8) -> Tuple[int, int]:
+    """Frontier disc counts for Black and White; fewer is better."""
+    adj_empty = adjacent(~(black | white) & BOARD_MASKS[size])
+    return popcount(black & adj_empty), popcount(white & adj_empty)
+
+
+def interior_counts(black: int, white: int, size: int = 8) -> Tuple[int, int]:
+    """Discs with no empty neighbour for Black and White; the complement of frontier_counts."""
+    adj_empty = adjacent(~(black | white) & BOARD_MASKS[size])
+    return popcount(black & ~adj_empty), popcount(white & ~adj_empty)
+
+
+def corner_score(me: int, opp: int, size: int = 8) -> int:
+    me_c = popcount(me & CORNER_MASKS[size])
+    opp_c = popcount(opp & CORNER_MASKS[size])
     return (me_c - opp_c)
 
 
+CORNER_OWNED = 4
+CORNER_ACCESS = 3  # an open corner the opponent could take is nearly as bad as a lost one
+
+
+def corner_eval(pos: Position) -> int:
+    """Corners held for the side to move minus the opponent's, less a penalty per corner the opponent can take."""
+    me, opp = pos.me_opp()
+    access = popcount(legal_moves(opp, me, pos.size) & CORNER_MASKS[pos.size])
+    return CORNER_OWNED * corner_score(me, opp, pos.size) - CORNER_ACCESS * access
+
+
+def _edges(size: int) -> Tuple[int, int, int, int]:
+    # rank 1, the top rank, file A and the last file
+    rank = (1 << size) - 1
+    board = BOARD_MASKS[size]
+    return rank, rank << (8 * (size - 1)), FILE_A & board, (FILE_A << (size - 1)) & board
+
+
+EDGES_BY_SIZE = {n: _edges(n) for n in BOARD_MASKS}
+EDGES = EDGES_BY_SIZE[8]
+EDGE_FULL_BONUS = 8  # a fully owned edge can never be flipped
+
+
+def edge_control(pos: Position) -> int:
+    """Edge discs of the side to move minus the opponent's, plus a bonus per edge one side owns outright."""
+    me, opp = pos.me_opp()
+    score = 0
+    for edge in EDGES_BY_SIZE[pos.size]:
+        score += popcount(me & edge) - popcount(opp & edge)
+        if me & edge == edge:
+            score += EDGE_FULL_BONUS
+        elif opp & edge == edge:
+            score -= EDGE_FULL_BONUS
+    return score
+
+
+# game_phase() results
+PHASE_OPENING = 0
+PHASE_MIDGAME = 1
+PHASE_ENDGAME = 2
+OPENING_MIN_EMPTIES = 45
+ENDGAME_MAX_EMPTIES = 20  # few enough for the exact solver; also its default cap
+
+
+def game_phase(black: int, white: int) -> int:
+    """PHASE_OPENING above 44 empties, PHASE_ENDGAME once the exact solver can take over, PHASE_MIDGAME in between."""
+    empties = count_empties(black, white)
+    if empties >= OPENING_MIN_EMPTIES:
+        return PHASE_OPENING
+    if empties <= ENDGAME_MAX_EMPTIES:
+        return PHASE_ENDGAME
+    return PHASE_MIDGAME
+
+
+# position_flags() bits
+POS_CORNER_MOVE = 1     # side to move can take a corner
+POS_CORNER_THREAT = 2   # opponent could take a corner if it were their turn
+POS_ODD_REGION = 4      # some empty region has an odd number of squares
+POS_GAME_OVER = 8       # neither side can move
+
+
+def position_flags(pos: Position) -> int:
+    """Cheap volatility checks in one bitfield, e.g. for filtering self-play data."""
+    me, opp = pos.me_opp()
+    mine, theirs = both_legal_moves(me, opp, pos.size)
+    corners = CORNER_MASKS[pos.size]
+    flags = 0
+    if mine & corners:
+        flags |= POS_CORNER_MOVE
+    if theirs & corners:
+        flags |= POS_CORNER_THREAT
+    if odd_empties(pos.black, pos.white, pos.size):
+        flags |= POS_ODD_REGION
+    if not mine and not theirs:
+        flags |= POS_GAME_OVER
+    return flags
+
+
+# Each corner with its X-square and two C-squares
+CORNER_DANGER: Tuple[Tuple[int, int], ...] = (
+    (0, (1 << 9) | (1 << 1) | (1 << 8)),
+    (7, (1 << 14) | (1 << 6) | (1 << 15)),
+    (56, (1 << 49) | (1 << 48) | (1 << 57)),
+    (63, (1 << 54) | (1 << 55) | (1 << 62)),
+)
+DANGER_MASK = sum(squares for _, squares in CORNER_DANGER)  # the four masks are disjoint
+
+
 def corner_adjacent_penalty(me: int, opp: int) -> int:
     # Penalise occupying X/C squares early; approximate via adjacency to corners
-    # X-squares: B2, G2, B7, G7 (indices 9, 14, 49, 54)
-    xs = (1 << 9) | (1 << 14) | (1 << 49) | (1 << 54)
-    cs = (
-        (1 << 1) | (1 << 8) | (1 << 9) |
-        (1 << 6) | (1 << 14) | (1 << 15) |
-        (1 << 48) | (1 << 49) | (1 << 56) |
-        (1 << 54) | (1 << 55) | (1 << 62)
-    )
-    me_bad = popcount(me & (xs | cs))
-    opp_bad = popcount(opp & (xs | cs))
-    return me_bad - opp_bad
-
-
-def evaluate(pos: Position, weights: EvalWeights = DEFAULT_WEIGHTS) -> int:
-    # Return centipawn-like score from side-to-move perspective (positive is good for stm)
+    return popcount(me & DANGER_MASK) - popcount(opp & DANGER_MASK)
+
+
+def _corner_danger(size: int) -> Tuple[Tuple[int, int], ...]:
+    # the on-board neighbours of each corner are its X- and C-squares
+    return tuple((sq, adjacent(1 << sq) & BOARD_MASKS[size]) for sq in bits(CORNER_MASKS[size]))
+
+
+CORNER_DANGER_BY_SIZE = {n: _corner_danger(n) for n in BOARD_MASKS}
+
+
+def dangerous_squares(pos: Position) -> List[int]:
+    """Legal moves on an X- or C-square whose corner is still empty, for coaching warnings."""
+    occ = pos.black | pos.white
+    danger = 0
+    for corner, squares in CORNER_DANGER_BY_SIZE[pos.size]:
+        if not (occ >> corner) & 1:
+            danger |= squares
+    return [sq for sq in pos.legal_squares() if (danger >> sq) & 1]
+
+
+# Classic static square weights, A1..H8: corners high, X/C squares negative.
+SQUARE_WEIGHTS: Tuple[int, ...] = (
+    100, -20,  10,   5,   5,  10, -20, 100,
+    -20, -50,  -2,  -2,  -2,  -2, -50, -20,
+     10,  -2,  -1,  -1,  -1,  -1,  -2,  10,
+      5,  -2,  -1,  -1,  -1,  -1,  -2,   5,
+      5,  -2,  -1,  -1,  -1,  -1,  -2,   5,
+     10,  -2,  -1,  -1,  -1,  -1,  -2,  10,
+    -20, -50,  -2,  -2,  -2,  -2, -50, -20,
+    100, -20,  10,   5,   5,  10, -20, 100,
+)
+
+
+def positional_score(me: int, opp: int, weights: Sequence[int] = SQUARE_WEIGHTS) -> int:
+    """Sum of square weights under `me`'s discs minus those under `opp`'s."""
+    if len(weights) != 64:
+        raise ValueError(f"Expected 64 square weights, got {len(weights)}")
+    score = 0
+    for sq in range(64):
+        bit = 1 << sq
+        if me & bit:
+            score += weights[sq]
+        elif opp & bit:
+            score -= weights[sq]
+    return score
+
+
+# feature_vector() layout, all from the side-to-move's perspective
+FEAT_MOBILITY = 0       # legal moves, own minus opponent's
+FEAT_POT_MOBILITY = 1   # potential mobility differential
+FEAT_STABILITY = 2      # stable discs, own minus opponent's
+FEAT_FRONTIER = 3       # frontier discs, own minus opponent's
+FEAT_CORNERS = 4        # corners held, own minus opponent's
+FEAT_DISCS = 5          # disc count, own minus opponent's
+FEAT_EMPTIES = 6        # empty squares
+FEATURE_COUNT = 7
+
+
+def feature_vector(pos: Position) -> List[int]:
     me, opp = pos.me_opp()
-    mob_me = popcount(legal_moves(me, opp))
-    mob_opp = popcount(legal_moves(opp, me))
-    pot_mob = potential_mobility(me, opp) - potential_mobility(opp, me)
+    size = pos.size
+    pot_me, pot_opp = potential_mobility_split(me, opp, size)
+    return [
+        mobility_diff(me, opp, size),
+        pot_me - pot_opp,
+        stability_diff(pos),
+        frontier_discs(me, opp, size) - frontier_discs(opp, me, size),
+        corner_score(me, opp, size),
+        popcount(me) - popcount(opp),
+        count_empties(me, opp, size),
+    ]
+
+
+def linear_eval(pos: Position, weights: Sequence[int]) -> int:
+    """Weighted sum of feature_vector(pos), one weight per FEAT_* index; for tuning from Python."""
+    if len(weights) != FEATURE_COUNT:
+        raise ValueError(f"Expected {FEATURE_COUNT} feature weights, got {len(weights)}")
+    return sum(w * f for w, f in zip(weights, feature_vector(pos)))
+
+
+def _feature_row(row: Tuple[int, int, int]) -> List[int]:
+    b, w, stm = row
+    return feature_vector(Position(int(b), int(w), int(stm)))
+
+
+def feature_matrix(black: Iterable[int], white: Iterable[int], stm: Iterable[int],
+                   workers: int = 1) -> List[List[int]]:
+    """feature_vector for many positions, one row each in FEAT_* column order.
+
+    Inputs may be any equal-length sequences of ints (e.g. uint64/uint8 arrays);
+    workers > 1 splits the rows across processes.
+    """
+    rows = list(zip(black, white, stm, strict=True))
+    if workers <= 1:
+        return [_feature_row(r) for r in rows]
+    with Pool(processes=workers) as pool:
+        return pool.map(_feature_row, rows, chunksize=max(1, len(rows) // (4 * workers)))
+
+
+def evaluate(pos: Position, weights: EvalWeights = DEFAULT_WEIGHTS,
+             squares: Optional[Sequence[int]] = None) -> int:
+    # Return centipawn-like score from side-to-move perspective (positive is good for stm).
+    # `squares` adds positional_score() with that 64-entry table, e.g. SQUARE_WEIGHTS.
+    check_eval_size(pos)
+    me, opp = pos.me_opp()
+    mobility = mobility_diff(me, opp)
+    pot_me, pot_opp = potential_mobility_split(me, opp)
+    pot_mob = pot_me - pot_opp
     corners_delta = corner_score(me, opp)
     corner_adj = corner_adjacent_penalty(me, opp)
     frontier = frontier_discs(me, opp) - frontier_discs(opp, me)
+    stability = stability_diff(pos)
     disc = pos.score_disc_diff()
     if pos.stm == 1:
         disc = -disc
     # Phase blend: as board fills, rely more on disc diff
-    empties = 64 - (popcount(pos.black | pos.white))
+    empties = count_empties(pos.black, pos.white)
     phase = max(0, min(64, empties)) / 64.0
     score = 0
-    score += weights.mobility * (mob_me - mob_opp)
+    score += weights.mobility * mobility
     score += weights.pot_mobility * pot_mob
     score += weights.corners * corners_delta
     score += weights.corner_adj_penalty * corner_adj
     score += weights.frontier * frontier
+    score += weights.stability * stability
     score += int((1 - phase) * weights.disc_diff * disc)
+    if squares is not None:
+        score += positional_score(me, opp, squares)
     return score
 
 
+def evaluate_symmetric(pos: Position, weights: EvalWeights = DEFAULT_WEIGHTS,
+                       squares: Optional[Sequence[int]] = None) -> int:
+    """Mean of evaluate() over the 8 symmetric copies of the position, rounded down.
+
+    The built-in terms are already symmetric, so this only differs from evaluate()
+    when the `squares` table is not; it smooths that table out at 8 times the cost.
+    """
+    check_eval_size(pos)
+    total = 0
+    for k in range(8):
+        total += evaluate(Position(transform(pos.black, k), transform(pos.white, k), pos.stm), weights, squares)
+    return total // 8
+
+
+def best_move_heuristic(pos: Position, weights: EvalWeights = DEFAULT_WEIGHTS) -> Optional[int]:
+    """Move whose resulting position evaluates best for the side to move, one ply deep.
+
+    Fallback for positions too open for the exact solver. Equal scores prefer a
+    corner, then the lowest square; a move that ends the game scores its final
+    margin. None if the side to move must pass.
+    """
+    check_eval_size(pos)
+    best, best_key = None, None
+    for sq in pos.legal_squares():
+        nxt = apply_move(pos, sq)
+        me, opp = (nxt.black, nxt.white) if pos.stm == 0 else (nxt.white, nxt.black)
+        if nxt.terminal():
+            score = final_margin(me, opp) * DISC_SCALE
+        else:
+            score = evaluate(nxt, weights)
+            if nxt.stm != pos.stm:
+                score = -score
+        key = (score, (CORNER_MASK >> sq) & 1)
+        if best_key is None or key > best_key:
+            best, best_key = sq, key
+    return best
+
+
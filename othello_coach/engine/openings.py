@@ -1,5 +1,8 @@
 from __future__ import annotations
-from typing import List, Tuple, Optional
+from typing import Dict, List, Tuple, Optional
+
+from .bitboard import Position, apply_move, flipped_squares
+from .symmetry import canonical_form, transform_square, INVERSE
 
 # Minimal embedded book with a few common names; extendable via JSON later.
 # Moves are given as 0..63 indices. We use algebra like "D3" externally.
@@ -16,10 +19,43 @@ FILES = "ABCDEFGH"
 
 
 def sq_to_alg(sq: int) -> str:
+    if not 0 <= sq < 64:
+        raise ValueError(f"Square out of range: {sq}")
     r, c = divmod(sq, 8)
     return f"{FILES[c]}{r+1}"
 
 
+def alg_to_sq(s: str) -> int:
+    """Inverse of sq_to_alg; accepts either case, e.g. "e6" or "E6"."""
+    t = s.strip().upper()
+    if len(t) != 2 or t[0] not in FILES or t[1] not in "12345678":
+        raise ValueError(f"Bad coordinate {s!r}")
+    return (int(t[1]) - 1) * 8 + FILES.index(t[0])
+
+
+def flipped_coords(pos: Position, sq: int) -> List[str]:
+    """Coordinates a move would flip, in animation order (outward from the move)."""
+    return [sq_to_alg(i) for i in flipped_squares(pos, sq)]
+
+
+def replay_transcript(moves: str) -> Position:
+    """Position after a transcript like "f5d6c3" from the standard start; passes are implicit.
+
+    Raises ValueError naming the 0-based move index of the first bad or illegal move.
+    """
+    t = "".join(moves.split())
+    if len(t) % 2:
+        raise ValueError(f"Transcript has an odd number of characters: {len(t)}")
+    pos = Position.initial()
+    for i in range(0, len(t), 2):
+        coord = t[i:i + 2]
+        try:
+            pos = apply_move(pos, alg_to_sq(coord))
+        except ValueError as e:
+            raise ValueError(f"Move {i // 2} ({coord}): {e}") from None
+    return pos
+
+
 def name_for_prefix(moves: List[int]) -> Optional[Tuple[str,str]]:
     # Return (name, variation) if prefix matches any book line
     best = None
@@ -32,3 +68,36 @@ def name_for_prefix(moves: List[int]) -> Optional[Tuple[str,str]]:
     return best
 
 
+# Book lines in standard notation. Each position along a line is stored once, under
+# its canonical form, with the next move; earlier lines win on conflict.
+BOOK_LINES = [
+    "F5 D6 C3 D3 C4 F4 C5 B3 C2",
+    "F5 F6 E6 F4 E3",
+    "F5 F4 E3 F6 D3",
+]
+
+
+def _build_book(lines: List[str]) -> Dict[int, int]:
+    book: Dict[int, int] = {}
+    for line in lines:
+        pos = Position.initial()
+        for tok in line.split():
+            sq = alg_to_sq(tok)
+            if not pos.is_legal(sq):
+                raise ValueError(f"Illegal book move {tok} in {line!r}")
+            canon, k = canonical_form(pos)
+            book.setdefault(canon.hash64(), transform_square(sq, k))
+            pos = apply_move(pos, sq)
+    return book
+
+
+BOOK = _build_book(BOOK_LINES)
+
+
+def book_move(pos: Position) -> Optional[int]:
+    """Book reply for `pos` in its own orientation, or None if out of book."""
+    canon, k = canonical_form(pos)
+    sq = BOOK.get(canon.hash64())
+    if sq is None:
+        return None
+    return transform_square(sq, INVERSE[k])
@@ -0,0 +1,102 @@
+from __future__ import annotations
+from dataclasses import dataclass
+from typing import List
+
+from .bitboard import popcount, adjacent, legal_moves, Position, BOARD_MASKS
+
+# Empty-square regions for endgame parity. Regions are king-connected groups of
+# empties; in parity theory what matters is whether a region has an odd or even
+# number of squares, since that decides who gets the last move in it.
+
+BLACK, WHITE, NEUTRAL = 0, 1, 2
+
+
+@dataclass(frozen=True)
+class ParityRegion:
+    mask: int
+    size: int
+    is_odd: bool
+    controller: int  # side with more discs bordering the region, or NEUTRAL
+
+
+def determine_controller(region: int, black: int, white: int) -> int:
+    border = adjacent(region)
+    b = popcount(border & black)
+    w = popcount(border & white)
+    if b > w:
+        return BLACK
+    if w > b:
+        return WHITE
+    return NEUTRAL
+
+
+def region_last_mover(region: int, black: int, white: int, stm: int, size: int = 8) -> int:
+    """Who plays the last square of `region` if the two sides alternate inside it.
+
+    The side to move goes first if it can enter the region, otherwise the opponent
+    does; an odd region then ends with the first mover, an even one with the other.
+    NEUTRAL if neither side can enter it yet.
+    """
+    if stm not in (BLACK, WHITE):
+        raise ValueError(f"Side to move must be 0 or 1, got {stm}")
+    me, opp = (black, white) if stm == BLACK else (white, black)
+    if legal_moves(me, opp, size) & region:
+        first = stm
+    elif legal_moves(opp, me, size) & region:
+        first = 1 - stm
+    else:
+        return NEUTRAL
+    return first if popcount(region) & 1 else 1 - first
+
+
+def _region_masks(empty: int) -> List[int]:
+    masks = []
+    while empty:
+        region = empty & -empty
+        while True:
+            grown = (region | adjacent(region)) & empty
+            if grown == region:
+                break
+            region = grown
+        empty ^= region
+        masks.append(region)
+    return masks
+
+
+def parity_regions(black: int, white: int, size: int = 8) -> List[ParityRegion]:
+    """Split the empty squares into regions, ordered by their lowest square."""
+    regions = []
+    for region in _region_masks(~(black | white) & BOARD_MASKS[size]):
+        n = popcount(region)
+        regions.append(ParityRegion(region, n, n % 2 == 1, determine_controller(region, black, white)))
+    return regions
+
+
+def odd_empties(black: int, white: int, size: int = 8) -> int:
+    """Empty squares lying in odd-sized regions."""
+    odd = 0
+    for region in _region_masks(~(black | white) & BOARD_MASKS[size]):
+        if popcount(region) & 1:
+            odd |= region
+    return odd
+
+
+def parity_score(pos: Position) -> int:
+    """Odd regions the side to move can enter minus odd regions only the opponent can.
+
+    Moving first into an odd region tends to earn the last move there, so a positive
+    score means parity favours the side to move. Even regions don't count.
+    """
+    me, opp = pos.me_opp()
+    mine = legal_moves(me, opp, pos.size)
+    theirs = legal_moves(opp, me, pos.size)
+    score = 0
+    for region in _region_masks(~(pos.black | pos.white) & BOARD_MASKS[pos.size]):
+        if popcount(region) & 1:
+            if region & mine:
+                score += 1
+            elif region & theirs:
+                score -= 1
+    return score
+
+
@@ -5,8 +5,8 @@ import math
 import time
 import random
 
-from .bitboard import Position, legal_moves, popcount
-from .eval import evaluate
+from .bitboard import Position, legal_moves, popcount, final_margin
+from .eval import DISC_SCALE, evaluate
 
 TTEntry = Tuple[int, int, int, int]  # depth, score, flag, best_move
 TT = Dict[int, TTEntry]
@@ -65,6 +65,10 @@ class Searcher:
         best_score = -10**9
         pv: List[int] = []
         alpha, beta = -10**9, 10**9
+        if pos.terminal():
+            # nothing to search: the finished game's result is the score
+            score, _, _ = self._negamax(pos, 1, alpha, beta)
+            return Analysis(None, score, 0, [], self.nodes, int(1000*(time.time()-self.start_time)))
         for depth in range(1, cfg.max_depth+1):
             score, move, line = self._negamax(pos, depth, alpha, beta)
             if move is not None:
@@ -80,8 +84,7 @@ class Searcher:
         return Analysis(best_move, best_score, depth, pv, self.nodes, int(1000*(time.time()-self.start_time)))
 
     def _score_root_moves(self, pos: Position, depth: int) -> List[Tuple[int,int]]:
-        lm = pos.legal_mask()
-        moves = [i for i in range(64) if (lm >> i) & 1]
+        moves = pos.legal_squares()
         scored = []
         for m in moves:
             child = pos.apply(m)
@@ -99,7 +102,12 @@ class Searcher:
         if self.node_limit and self.nodes >= self.node_limit:
             return evaluate(pos), None, []
         key = pos.hash64()
-        if depth == 0 or pos.terminal():
+        if pos.terminal():
+            # Both sides pass (or the board is full): score the result, not the eval,
+            # on the evaluation's DISC_SCALE like the endgame solver.
+            me, opp = pos.me_opp()
+            return final_margin(me, opp, pos.size) * DISC_SCALE, None, []
+        if depth == 0:
             return evaluate(pos), None, []
         if key in self.tt:
             td, ts, tf, tm = self.tt[key]
@@ -125,7 +133,7 @@ class Searcher:
             flag = FLAG_EXACT
             self.tt[key] = (depth, best_score, flag, best_move if best_move is not None else 64)
             return best_score, best_move, [best_move] + pv if best_move is not None else []
-        moves = [i for i in range(64) if (lm >> i) & 1]
+        moves = pos.legal_squares()
         # Simple move ordering: prefer corners, then eval guess
         def move_key(m):
             if m in (0,7,56,63):
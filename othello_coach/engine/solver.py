@@ -0,0 +1,734 @@
+from __future__ import annotations
+from dataclasses import dataclass
+from multiprocessing import Pool
+from typing import Callable, List, Optional, Tuple
+import random
+import time
+
+from .bitboard import (
+    Position, PASS, apply_move, legal_moves, all_flips, flips_for_move, popcount, count_empties, final_margin, is_game_over,
+    zobrist, CORNER_MASKS, KernelError,
+)
+from .search import FLAG_EXACT, FLAG_ALPHA, FLAG_BETA
+from .eval import DISC_SCALE, ENDGAME_MAX_EMPTIES, check_eval_size, evaluate
+from .parity import odd_empties
+from .stability import stable_discs
+
+# Exact endgame solver. Scores are final disc margins from the side-to-move's
+# perspective, with empty squares awarded to the winner.
+# Internally margins are scaled by DISC_SCALE so that depth-limited searches can
+# score their horizon with evaluate() on the same scale.
+
+SolverSlot = Tuple[int, int, int, int, int, int]  # me, opp, draft, score, flag, best square (-1 if none)
+
+MAX_MARGIN = 64
+MAX_SCORE = MAX_MARGIN * DISC_SCALE
+FULL_DEPTH = 64  # deep enough to reach the end of any game; passes don't use up depth
+DEFAULT_MAX_EMPTIES = ENDGAME_MAX_EMPTIES
+# A solve here takes tens of seconds at 16 empties and many minutes at 18, so past
+# 16 it is usually the time budget, not the cap, that ends a default solve.
+DEFAULT_TIME_MS = 60_000
+DEFAULT_TT_MB = 64
+CLOCK_CHECK_NODES = 1024
+KILLER_BONUS = 2  # in quarter replies
+ASPIRATION_DELTA = 4 * DISC_SCALE
+ETC_MIN_EMPTIES = 7  # below this, probing every child costs more than it saves
+PARITY_MAX_EMPTIES = 14
+PARITY_BONUS = 4  # in quarter replies
+# Rough CPython footprint of one slot: list pointer plus the slot tuple and its
+# two 64-bit key ints.
+TT_ENTRY_BYTES = 256
+
+
+def tt_capacity(tt_mb: int) -> int:
+    return max(2, tt_mb * 1024 * 1024 // TT_ENTRY_BYTES)
+
+
+@dataclass
+class TTStats:
+    probes: int = 0
+    hits: int = 0
+    stores: int = 0
+    collisions: int = 0  # stores that overwrote a different position
+
+
+@dataclass
+class SearchStats:
+    nodes: int = 0
+    leaf_nodes: int = 0      # scored by the game result, a stability bound or the static eval
+    interior_nodes: int = 0  # searched at least one move, or passed
+    cutoff_nodes: int = 0    # interior nodes that failed high
+    tt_cutoffs: int = 0      # answered by a TT entry, including ETC; nodes is the sum of these three
+    tt_hits: int = 0
+    tt_stores: int = 0
+    elapsed_ms: int = 0
+    completed: bool = False
+
+
+class TranspositionTable:
+    """Fixed-size table of two-slot buckets indexed by hash % buckets.
+
+    The first slot is depth-preferred: it only yields to an entry searched at
+    least as deep. The second slot always takes whatever the first one rejected.
+    """
+
+    def __init__(self, tt_mb: int = DEFAULT_TT_MB):
+        self.buckets = tt_capacity(tt_mb) // 2
+        self.slots: List[Optional[SolverSlot]] = [None] * (2 * self.buckets)
+        self.stats = TTStats()
+
+    def clear(self):
+        self.slots = [None] * (2 * self.buckets)
+        self.stats = TTStats()
+
+    def probe(self, me: int, opp: int) -> Optional[SolverSlot]:
+        self.stats.probes += 1
+        i = 2 * (hash((me, opp)) % self.buckets)
+        for slot in (self.slots[i], self.slots[i + 1]):
+            if slot is not None and slot[0] == me and slot[1] == opp:
+                self.stats.hits += 1
+                return slot
+        return None
+
+    def store(self, me: int, opp: int, draft: int, score: int, flag: int, best_sq: int = -1):
+        self.stats.stores += 1
+        i = 2 * (hash((me, opp)) % self.buckets)
+        deep = self.slots[i]
+        if deep is not None and not (deep[0] == me and deep[1] == opp) and deep[2] > draft:
+            i += 1
+        old = self.slots[i]
+        if old is not None and not (old[0] == me and old[1] == opp):
+            self.stats.collisions += 1
+        self.slots[i] = (me, opp, draft, score, flag, best_sq)
+
+    def entries(self) -> List[SolverSlot]:
+        return [slot for slot in self.slots if slot is not None]
+
+
+class SearchCancelled(TimeoutError):
+    """Raised when a progress callback asks the solver to stop.
+
+    A TimeoutError, so callers already handling an exhausted budget handle it too.
+    """
+
+
+# progress(nodes, elapsed_ms) -> keep going?
+ProgressCallback = Callable[[int, int], bool]
+# Endgame database hooks, keyed by Position.hash64() with scores as solve() returns them
+DbProbe = Callable[[int], Optional[int]]
+DbStore = Callable[[int, int], None]
+
+
+def check_empties(pos: Position, max_empties: int):
+    empties = count_empties(pos.black, pos.white, pos.size)
+    if empties > max_empties:
+        raise KernelError(f"{empties} empties exceeds solver cap of {max_empties}")
+
+
+class ExactSolver:
+    def __init__(self, tt_mb: int = DEFAULT_TT_MB, max_empties: int = DEFAULT_MAX_EMPTIES,
+                 time_ms: int = DEFAULT_TIME_MS, cutoff_heuristics: bool = False, max_nodes: int = 0,
+                 quiescence: bool = False, stability_cutoff: bool = False, progress: Optional[ProgressCallback] = None,
+                 tt: Optional[TranspositionTable] = None, db_probe: Optional[DbProbe] = None,
+                 db_store: Optional[DbStore] = None):
+        # Pass `tt` to share one table between solvers, including solvers on other
+        # threads: slots are whole tuples checked against the position on probe, so a
+        # racing store can only lose an entry, never corrupt one.
+        self.tt = tt if tt is not None else TranspositionTable(tt_mb)
+        self.nodes = 0
+        self.leaf_nodes = 0
+        self.interior_nodes = 0
+        self.cutoff_nodes = 0
+        self.tt_cutoffs = 0
+        # this solver's own share of the table traffic; tt.stats counts every user of a shared table
+        self.tt_hits = 0
+        self.tt_stores = 0
+        self.max_empties = max_empties
+        self.time_ms = time_ms  # 0 = unlimited
+        self.deadline = 0.0
+        self.max_nodes = max_nodes  # per search, 0 = unlimited
+        self.node_limit = 0
+        self.progress = progress  # called every CLOCK_CHECK_NODES nodes
+        self.started = 0.0
+        # solve() asks db_probe about the root and its children before searching, and
+        # hands each exact root result to db_store, so an on-disk table can back the
+        # solver. Deeper nodes are not probed: the search keys them by (me, opp) and
+        # does not know which colour is to move, which the Zobrist hash needs.
+        self.db_probe = db_probe
+        self.db_store = db_store
+        # Optional cutoff heuristics, kept across searches: two killer moves per empties
+        # count and a per-square history score. At the depths reachable here they are
+        # roughly node-neutral, so they are off unless asked for.
+        self.cutoff_heuristics = cutoff_heuristics
+        self.killers = [[0, 0] for _ in range(65)]
+        self.history = [0] * 64
+        # Depth-limited searches only: at the horizon keep playing corner captures
+        # before trusting the static eval.
+        self.quiescence = quiescence
+        # Exact searches only: cut off when stable discs alone bound the final margin
+        # outside the window.
+        self.stability_cutoff = stability_cutoff
+        # Board geometry, taken from each root position
+        self.size = 8
+        self.squares = 64
+        self.corners = CORNER_MASKS[8]
+
+    def clear(self):
+        """Reset this solver's counters and cutoff heuristics.
+
+        The TT is left alone, since other solvers may share it: whoever owns the
+        table empties it with tt.clear(). Otherwise it carries over between solves.
+        """
+        self.nodes = self.leaf_nodes = self.interior_nodes = self.cutoff_nodes = self.tt_cutoffs = 0
+        self.tt_hits = self.tt_stores = 0
+        self.killers = [[0, 0] for _ in range(65)]
+        self.history = [0] * 64
+
+    def _root(self, pos: Position) -> Tuple[int, int]:
+        check_empties(pos, self.max_empties)
+        self.size, self.squares, self.corners = pos.size, pos.size * pos.size, CORNER_MASKS[pos.size]
+        self.started = time.time()
+        self.deadline = time.time() + self.time_ms / 1000.0 if self.time_ms else 0.0
+        self.node_limit = self.nodes + self.max_nodes if self.max_nodes else 0
+        return pos.me_opp()
+
+    def solve(self, pos: Position) -> int:
+        """Exact final disc margin for the side to move under perfect play.
+
+        Raises TimeoutError if `time_ms` or `max_nodes` runs out first, or
+        SearchCancelled if `progress` returns False; nothing from the aborted
+        search is returned.
+        """
+        me, opp = self._root(pos)
+        key = pos.hash64()
+        if self.db_probe:
+            known = self.db_probe(key)
+            if known is not None:
+                return known
+            self._seed_from_db(pos)
+        score = self._aspiration(me, opp, self._seed(me, opp)) // DISC_SCALE
+        if self.db_store:
+            self.db_store(key, score)
+        return score
+
+    def _seed_from_db(self, pos: Position):
+        """Store each root child the database knows as an exact TT entry, so the search skips it."""
+        for sq in pos.legal_squares():
+            child = apply_move(pos, sq)
+            known = self.db_probe(child.hash64())
+            if known is not None:
+                me, opp = child.me_opp()
+                self.tt_stores += 1
+                self.tt.store(me, opp, count_empties(me, opp, child.size), known * DISC_SCALE, FLAG_EXACT)
+
+    def wld(self, pos: Position) -> int:
+        """1/0/-1 for a win/draw/loss of the side to move; searches the [-1, 1] window only."""
+        me, opp = self._root(pos)
+        s = self._negamax(me, opp, -1, 1)
+        return (s > 0) - (s < 0)
+
+    def window(self, pos: Position, alpha: int, beta: int) -> int:
+        """One alpha-beta search of the (alpha, beta) window, in discs.
+
+        A result strictly inside the window is exact; one <= alpha is only an upper
+        bound and one >= beta only a lower bound. TT entries keep their bound type,
+        so calls with different windows can share a solver.
+        """
+        if not -MAX_MARGIN <= alpha < beta <= MAX_MARGIN:
+            raise ValueError(f"Window must satisfy -{MAX_MARGIN} <= alpha < beta <= {MAX_MARGIN}, got ({alpha}, {beta})")
+        me, opp = self._root(pos)
+        return self._negamax(me, opp, alpha * DISC_SCALE, beta * DISC_SCALE) // DISC_SCALE
+
+    def mtdf(self, pos: Position) -> int:
+        """Same result as solve(), found by a series of null-window searches (MTD(f))."""
+        me, opp = self._root(pos)
+        g = self._seed(me, opp)
+        lower, upper = -MAX_SCORE, MAX_SCORE
+        while lower < upper:
+            beta = max(g, lower + 1)
+            g = self._negamax(me, opp, beta - 1, beta)
+            if g < beta:
+                upper = g
+            else:
+                lower = g
+        return g // DISC_SCALE
+
+    def multipv(self, pos: Position, n: int) -> List[Tuple[int, int]]:
+        """Exact (square, margin) for the best `n` root moves, best first.
+
+        Every root move is solved with its own window, so the scores of the
+        non-best moves are exact too. Empty if the side to move must pass.
+        """
+        if n < 1:
+            raise KernelError(f"Need at least one line, got n={n}")
+        me, opp = self._root(pos)
+        scored = []
+        for sq, flips in all_flips(me, opp, self.size):
+            move = 1 << sq
+            c_me, c_opp = opp ^ flips, me | flips | move
+            scored.append((sq, -self._aspiration(c_me, c_opp, self._seed(c_me, c_opp)) // DISC_SCALE))
+        scored.sort(key=lambda e: (-e[1], e[0]))
+        return scored[:n]
+
+    def final_position(self, pos: Position) -> Position:
+        """The finished board reached when both sides follow a perfect-play line from `pos`."""
+        score = self.solve(pos)
+        while not pos.terminal():
+            me, opp = pos.me_opp()
+            hint = self.best_move(me, opp)
+            moves = pos.legal_squares() or [PASS]
+            if hint in moves:
+                moves.remove(hint)
+                moves.insert(0, hint)
+            for sq in moves:
+                child = apply_move(pos, sq)
+                # the mover keeps its margin; a forced pass by the opponent keeps stm
+                child_score = self.solve(child)
+                if (child_score if child.stm == pos.stm else -child_score) == score:
+                    break
+            pos, score = child, child_score
+        return pos
+
+    def root_report(self, pos: Position) -> List[Tuple[int, int, int]]:
+        """(square, exact margin, subtree nodes) per root move, in search order.
+
+        Each child gets a full window, so every score is exact; the root itself is
+        counted as one node, so the subtree counts sum to self.nodes - 1 for a
+        fresh solver.
+        """
+        me, opp = self._root(pos)
+        self.nodes += 1
+        self.interior_nodes += 1
+        report = []
+        for move, flips in self._order_moves(me, opp, self.squares - popcount(me | opp)):
+            before = self.nodes
+            score = -self._negamax(opp ^ flips, me | flips | move, -MAX_SCORE, MAX_SCORE)
+            report.append((move.bit_length() - 1, score // DISC_SCALE, self.nodes - before))
+        return report
+
+    def dump_tt(self) -> List[Tuple[int, int, int, int]]:
+        """(hash, score, draft, flag) for every live TT entry.
+
+        The search keys entries by the side to move's and the opponent's discs, so
+        they carry no colour: hash is the public Zobrist key of the position with the
+        side to move as Black, which is pos.hash64() for a Black-to-move `pos`.
+        Scores are in DISC_SCALE units from the side to move's point of view and
+        flag is FLAG_EXACT, FLAG_ALPHA or FLAG_BETA.
+        """
+        return [(zobrist(me, opp, 0), score, draft, flag) for me, opp, draft, score, flag, _ in self.tt.entries()]
+
+    def best_move(self, me: int, opp: int) -> Optional[int]:
+        """Best square recorded in the TT for this position, if any."""
+        entry = self.tt.probe(me, opp)
+        if entry is None or entry[5] < 0:
+            return None
+        return entry[5]
+
+    def _order_moves(self, me: int, opp: int, empties: int) -> List[Tuple[int, int]]:
+        """Return (move, flips) pairs: corners first, then by fewest opponent replies.
+
+        Near the end, a move into an odd empty region counts as PARITY_BONUS fewer
+        replies. With cutoff heuristics on, killer moves count as KILLER_BONUS fewer replies
+        and history breaks ties.
+        """
+        killers = self.killers[empties]
+        size = self.size
+        odd = odd_empties(me, opp, size) if empties <= PARITY_MAX_EMPTIES else 0
+        scored = []
+        for sq, flips in all_flips(me, opp, size):
+            move = 1 << sq
+            key = 4 * popcount(legal_moves(opp ^ flips, me | flips | move, size))
+            if move & self.corners:
+                key -= 256
+            if move & odd:
+                key -= PARITY_BONUS
+            history = 0
+            if self.cutoff_heuristics:
+                if move == killers[0] or move == killers[1]:
+                    key -= KILLER_BONUS
+                history = -self.history[sq]
+            scored.append((key, history, move, flips))
+        scored.sort()
+        return [(move, flips) for *_, move, flips in scored]
+
+    def _record_cutoff(self, move: int, empties: int, draft: int):
+        killers = self.killers[empties]
+        if killers[0] != move:
+            killers[1] = killers[0]
+            killers[0] = move
+        self.history[move.bit_length() - 1] += draft * draft
+
+    def _seed(self, me: int, opp: int) -> int:
+        # Final margins are always even, so guess the nearest even margin to the static eval.
+        return round(self._static(me, opp) / (2 * DISC_SCALE)) * 2 * DISC_SCALE
+
+    def _aspiration(self, me: int, opp: int, guess: int, depth: int = FULL_DEPTH) -> int:
+        """Search a window around `guess`, doubling its width on whichever side fails."""
+        delta = ASPIRATION_DELTA
+        alpha = max(-MAX_SCORE, guess - delta)
+        beta = min(MAX_SCORE, guess + delta)
+        while True:
+            s = self._negamax(me, opp, alpha, beta, depth)
+            if s <= alpha and alpha > -MAX_SCORE:
+                alpha = max(-MAX_SCORE, s - delta)
+            elif s >= beta and beta < MAX_SCORE:
+                beta = min(MAX_SCORE, s + delta)
+            else:
+                return s
+            delta *= 2
+
+    def _static(self, me: int, opp: int) -> int:
+        if self.size != 8:
+            return 0  # only exact searches run on other boards; this is just the aspiration guess
+        return max(-MAX_SCORE + 1, min(MAX_SCORE - 1, evaluate(Position(me, opp, 0))))
+
+    def _quiesce(self, me: int, opp: int, alpha: int, beta: int) -> int:
+        """Static eval, unless taking a corner scores better for the side to move."""
+        self.nodes += 1
+        if is_game_over(me, opp, self.size):
+            self.leaf_nodes += 1
+            return final_margin(me, opp, self.size) * DISC_SCALE
+        best = self._static(me, opp)
+        corners = legal_moves(me, opp, self.size) & self.corners
+        if corners and best < beta:
+            self.interior_nodes += 1
+        else:
+            self.leaf_nodes += 1
+        while corners and best < beta:
+            move = corners & -corners
+            corners ^= move
+            flips = flips_for_move(me, opp, move)
+            best = max(best, -self._quiesce(opp ^ flips, me | flips | move, -beta, -max(alpha, best)))
+        return best
+
+    def _negamax(self, me: int, opp: int, alpha: int, beta: int, depth: int = FULL_DEPTH) -> int:
+        self.nodes += 1
+        if self.nodes % CLOCK_CHECK_NODES == 0 and (self.deadline or self.progress):
+            now = time.time()
+            if self.deadline and now > self.deadline:
+                raise TimeoutError(f"solver exceeded {self.time_ms} ms after {self.nodes} nodes")
+            if self.progress and not self.progress(self.nodes, int(1000 * (now - self.started))):
+                raise SearchCancelled(f"solver cancelled after {self.nodes} nodes")
+        if self.node_limit and self.nodes > self.node_limit:
+            raise TimeoutError(f"solver exceeded its budget of {self.max_nodes} nodes")
+        if not me or not opp:
+            # wipeout: the survivor gets every square
+            self.leaf_nodes += 1
+            return final_margin(me, opp, self.size) * DISC_SCALE
+        empties = self.squares - popcount(me | opp)
+        draft = min(depth, empties)
+        entry = self.tt.probe(me, opp)
+        if entry is not None:
+            self.tt_hits += 1
+            if entry[2] >= draft:
+                ts, tf = entry[3], entry[4]
+                if tf == FLAG_EXACT or (tf == FLAG_BETA and ts >= beta) or (tf == FLAG_ALPHA and ts <= alpha):
+                    self.tt_cutoffs += 1
+                    return ts
+        if self.stability_cutoff and depth >= empties:
+            # Own stable discs are kept to the end, so the margin is at least
+            # 2 * stable - squares; likewise the opponent's cap it from above.
+            n = self.squares
+            if DISC_SCALE * (n - 2 * popcount(opp)) <= alpha:
+                upper = DISC_SCALE * (n - 2 * popcount(stable_discs(opp, me, self.size)))
+                if upper <= alpha:
+                    self.leaf_nodes += 1
+                    return upper
+            if DISC_SCALE * (2 * popcount(me) - n) >= beta:
+                lower = DISC_SCALE * (2 * popcount(stable_discs(me, opp, self.size)) - n)
+                if lower >= beta:
+                    self.leaf_nodes += 1
+                    return lower
+        if depth == 0:
+            if is_game_over(me, opp, self.size):
+                self.leaf_nodes += 1
+                return final_margin(me, opp, self.size) * DISC_SCALE
+            if self.quiescence:
+                # hands the position on to _quiesce, which counts it again as its own root
+                self.interior_nodes += 1
+                return self._quiesce(me, opp, alpha, beta)
+            self.leaf_nodes += 1
+            return self._static(me, opp)
+        ordered = self._order_moves(me, opp, empties)
+        if not ordered:
+            # Pass: same empties and depth, other side to move. If the opponent
+            # can't move either the game is over, however many squares are empty.
+            if not legal_moves(opp, me, self.size):
+                self.leaf_nodes += 1
+                return final_margin(me, opp, self.size) * DISC_SCALE
+            self.interior_nodes += 1
+            return -self._negamax(opp, me, -beta, -alpha, depth)
+        if empties >= ETC_MIN_EMPTIES:
+            # Enhanced transposition cutoff: a child whose stored upper bound already
+            # proves -score >= beta refutes this node without searching anything.
+            tt = self.tt
+            for move, flips in ordered:
+                child = tt.probe(opp ^ flips, me | flips | move)
+                if child is None:
+                    continue
+                self.tt_hits += 1
+                if child[2] >= draft - 1 and child[4] != FLAG_BETA and -child[3] >= beta:
+                    self.tt_cutoffs += 1
+                    return -child[3]
+        self.interior_nodes += 1
+        orig_alpha = alpha
+        best = -MAX_SCORE - 1
+        best_move = 0
+        first = True
+        for move, flips in ordered:
+            child_me, child_opp = opp ^ flips, me | flips | move
+            if first:
+                s = -self._negamax(child_me, child_opp, -beta, -alpha, depth - 1)
+                first = False
+            else:
+                # PVS: prove the move is no better than alpha, re-search only if it is
+                s = -self._negamax(child_me, child_opp, -alpha - 1, -alpha, depth - 1)
+                if alpha < s < beta:
+                    s = -self._negamax(child_me, child_opp, -beta, -s, depth - 1)
+            if s > best:
+                best = s
+                best_move = move
+                if s > alpha:
+                    alpha = s
+                    if alpha >= beta:
+                        self.cutoff_nodes += 1
+                        if self.cutoff_heuristics:
+                            self._record_cutoff(move, empties, draft)
+                        break
+        flag = FLAG_EXACT
+        if best <= orig_alpha:
+            flag = FLAG_ALPHA
+        elif best >= beta:
+            flag = FLAG_BETA
+        self.tt_stores += 1
+        self.tt.store(me, opp, draft, best, flag, best_move.bit_length() - 1)
+        return best
+
+
+def solve_exact(pos: Position, tt_mb: int = DEFAULT_TT_MB, max_empties: int = DEFAULT_MAX_EMPTIES,
+                time_ms: int = DEFAULT_TIME_MS, max_nodes: int = 0, progress: Optional[ProgressCallback] = None,
+                db_probe: Optional[DbProbe] = None, db_store: Optional[DbStore] = None) -> int:
+    """Final disc margin in -64..64, unscaled as endgame databases and Edax report it.
+
+    Multiply by DISC_SCALE to compare with search_depth or solve_iterative scores.
+    """
+    return ExactSolver(tt_mb, max_empties, time_ms, max_nodes=max_nodes, progress=progress,
+                       db_probe=db_probe, db_store=db_store).solve(pos)
+
+
+# solve_exact_persp() score perspectives
+PERSPECTIVE_STM = 0    # positive is good for the side to move, as solve_exact
+PERSPECTIVE_BLACK = 1  # positive is good for Black whoever is to move
+
+
+def solve_exact_persp(pos: Position, perspective: int = PERSPECTIVE_STM, tt_mb: int = DEFAULT_TT_MB,
+                      max_empties: int = DEFAULT_MAX_EMPTIES, time_ms: int = DEFAULT_TIME_MS) -> int:
+    """solve_exact with the margin reported from a chosen perspective."""
+    if perspective not in (PERSPECTIVE_STM, PERSPECTIVE_BLACK):
+        raise ValueError(f"Unknown perspective: {perspective}")
+    score = solve_exact(pos, tt_mb, max_empties, time_ms)
+    if perspective == PERSPECTIVE_BLACK and pos.stm == 1:
+        return -score
+    return score
+
+
+def solve_edax_format(pos: Position, tt_mb: int = DEFAULT_TT_MB) -> int:
+    """Exact score as Edax prints it, for diffing against its output.
+
+    Edax scores the final disc difference from the side to move's point of view with
+    every empty square left at game end (after a wipeout or when both sides must
+    pass) added to the winner's count; a draw stays 0 whatever is left empty. That
+    is the same convention final_margin and solve_exact use. Unlike solve_exact
+    there is no empties limit or time budget, and only the standard 8x8 board is
+    accepted.
+    """
+    if pos.size != 8:
+        raise ValueError("Edax scores are only defined for the 8x8 board")
+    return solve_exact(pos, tt_mb, max_empties=count_empties(pos.black, pos.white), time_ms=0)
+
+
+def solve_wld(pos: Position, tt_mb: int = DEFAULT_TT_MB, max_empties: int = DEFAULT_MAX_EMPTIES,
+              time_ms: int = DEFAULT_TIME_MS, max_nodes: int = 0) -> int:
+    return ExactSolver(tt_mb, max_empties, time_ms, max_nodes=max_nodes).wld(pos)
+
+
+def is_drawn(pos: Position, tt_mb: int = DEFAULT_TT_MB, max_empties: int = DEFAULT_MAX_EMPTIES,
+             time_ms: int = DEFAULT_TIME_MS) -> bool:
+    """True if perfect play draws; an aborted search raises TimeoutError rather than answering."""
+    return solve_wld(pos, tt_mb, max_empties, time_ms) == 0
+
+
+def negamax_search(pos: Position, alpha: int, beta: int, tt_mb: int = DEFAULT_TT_MB,
+                   max_empties: int = DEFAULT_MAX_EMPTIES) -> int:
+    """ExactSolver.window() on a fresh solver: a bound outside (alpha, beta), exact inside it."""
+    return ExactSolver(tt_mb, max_empties).window(pos, alpha, beta)
+
+
+def solve_mtdf(pos: Position, tt_mb: int = DEFAULT_TT_MB, max_empties: int = DEFAULT_MAX_EMPTIES,
+               time_ms: int = DEFAULT_TIME_MS, max_nodes: int = 0) -> int:
+    return ExactSolver(tt_mb, max_empties, time_ms, max_nodes=max_nodes).mtdf(pos)
+
+
+def solve_multipv(pos: Position, n: int, tt_mb: int = DEFAULT_TT_MB,
+                  max_empties: int = DEFAULT_MAX_EMPTIES, time_ms: int = DEFAULT_TIME_MS) -> List[Tuple[int, int]]:
+    return ExactSolver(tt_mb, max_empties, time_ms).multipv(pos, n)
+
+
+def solve_final_board(pos: Position, tt_mb: int = DEFAULT_TT_MB,
+                      max_empties: int = DEFAULT_MAX_EMPTIES) -> Position:
+    return ExactSolver(tt_mb, max_empties).final_position(pos)
+
+
+def root_report(pos: Position, tt_mb: int = DEFAULT_TT_MB,
+                max_empties: int = DEFAULT_MAX_EMPTIES) -> List[Tuple[int, int, int]]:
+    return ExactSolver(tt_mb, max_empties).root_report(pos)
+
+
+def _solve_child(args: Tuple[Position, int]) -> int:
+    child, tt_mb = args
+    return ExactSolver(tt_mb, max_empties=64).solve(child)
+
+
+def solve_exact_parallel(pos: Position, workers: int = 2, tt_mb: int = DEFAULT_TT_MB,
+                         max_empties: int = DEFAULT_MAX_EMPTIES) -> int:
+    """solve_exact with the root moves split across worker processes.
+
+    Python threads can't search in parallel, so each process solves whole root
+    children with its own share of the TT budget; nothing is shared between them.
+    """
+    check_empties(pos, max_empties)
+    moves = pos.legal_squares()
+    if workers <= 1 or len(moves) < 2:
+        return solve_exact(pos, tt_mb, max_empties)
+    workers = min(workers, len(moves))
+    jobs = [(pos.apply(m), max(1, tt_mb // workers)) for m in moves]
+    with Pool(processes=workers) as pool:
+        scores = pool.map(_solve_child, jobs)
+    return max(-s for s in scores)
+
+
+def solve_exact_stats(pos: Position, tt_mb: int = DEFAULT_TT_MB, max_empties: int = DEFAULT_MAX_EMPTIES,
+                      time_ms: int = DEFAULT_TIME_MS, max_nodes: int = 0,
+                      progress: Optional[ProgressCallback] = None) -> Tuple[Optional[int], SearchStats]:
+    """Like solve_exact, but also report search statistics.
+
+    If the time or node budget runs out, or `progress` cancels the search, the
+    score is None and stats.completed is False.
+    """
+    solver = ExactSolver(tt_mb, max_empties, time_ms, max_nodes=max_nodes, progress=progress)
+    start = time.time()
+    score: Optional[int] = None
+    try:
+        score = solver.solve(pos)
+    except TimeoutError:
+        pass
+    stats = SearchStats(
+        nodes=solver.nodes,
+        leaf_nodes=solver.leaf_nodes,
+        interior_nodes=solver.interior_nodes,
+        cutoff_nodes=solver.cutoff_nodes,
+        tt_cutoffs=solver.tt_cutoffs,
+        tt_hits=solver.tt_hits,
+        tt_stores=solver.tt_stores,
+        elapsed_ms=int(1000 * (time.time() - start)),
+        completed=score is not None,
+    )
+    return score, stats
+
+
+def search_depth(pos: Position, depth: int, tt_mb: int = DEFAULT_TT_MB,
+                 quiescence: bool = False) -> Tuple[int, Optional[int]]:
+    """Negamax `depth` plies deep with the static evaluation at the horizon.
+
+    Returns (score, best square) with the score in evaluation units (DISC_SCALE
+    per disc); lines that finish the game within `depth` score the exact margin,
+    so at depth >= empties this is the exact solve. Passes don't use up depth.
+    With `quiescence`, corner captures available at the horizon are searched too.
+    """
+    if depth < 0:
+        raise ValueError(f"Depth must be non-negative, got {depth}")
+    check_eval_size(pos)
+    solver = ExactSolver(tt_mb, max_empties=64, time_ms=0, quiescence=quiescence)
+    me, opp = solver._root(pos)
+    score = solver._negamax(me, opp, -MAX_SCORE, MAX_SCORE, depth)
+    return score, solver.best_move(me, opp)
+
+
+def best_reply(pos: Position, depth: int = 1, tt_mb: int = DEFAULT_TT_MB) -> Optional[int]:
+    """Most likely move for the side to move, scoring each child by a `depth`-ply search.
+
+    Meant for previewing the opponent's answer after a student's move; depth 0
+    picks the move with the best static evaluation. None if the side must pass.
+    """
+    if depth < 0:
+        raise ValueError(f"Depth must be non-negative, got {depth}")
+    check_eval_size(pos)
+    solver = ExactSolver(tt_mb, max_empties=64, time_ms=0)
+    me, opp = solver._root(pos)
+    best, best_score = None, -MAX_SCORE - 1
+    for sq, flips in all_flips(me, opp, pos.size):
+        move = 1 << sq
+        score = -solver._negamax(opp ^ flips, me | flips | move, -MAX_SCORE, MAX_SCORE, depth)
+        if score > best_score:
+            best, best_score = sq, score
+    return best
+
+
+def solve_iterative(pos: Position, time_ms: int, tt_mb: int = DEFAULT_TT_MB) -> Tuple[int, Optional[int], bool]:
+    """Deepen a depth-limited search one ply at a time until the game end is reached or time runs out.
+
+    Returns (score, best square, exact) from the last completed iteration. The score
+    is in evaluation units (DISC_SCALE per disc); exact is True once an iteration
+    searched every line to the end of the game. The TT is shared by all iterations.
+    """
+    check_eval_size(pos)
+    solver = ExactSolver(tt_mb, max_empties=64, time_ms=time_ms)
+    me, opp = solver._root(pos)
+    empties = count_empties(me, opp, pos.size)
+    deadline, solver.deadline = solver.deadline, 0.0  # always finish the first ply
+    result: Tuple[int, Optional[int], bool] = (solver._static(me, opp), None, False)
+    depth = 1
+    while True:
+        try:
+            score = solver._aspiration(me, opp, result[0], depth)
+        except TimeoutError:
+            break
+        result = (score, solver.best_move(me, opp), depth >= empties)
+        solver.deadline = deadline
+        if depth >= empties:
+            break
+        depth += 1
+    return result
+
+
+def self_play(depth: int, seed: int = 0, tt_mb: int = DEFAULT_TT_MB, margin: int = 0) -> List[int]:
+    """Moves of a whole game from the initial position, both sides choosing by a `depth`-ply search.
+
+    Each legal move is scored like search_depth would score it; the move is drawn by
+    a Random(seed) from those within `margin` (evaluation units) of the best, so
+    margin 0 only breaks ties. Forced passes are left implicit, as apply_move plays
+    them, so the list replays with apply_move or as a transcript. The TT is kept
+    for the whole game, and the same arguments always give the same game.
+    """
+    if depth < 1:
+        raise ValueError(f"Depth must be positive, got {depth}")
+    if margin < 0:
+        raise ValueError(f"Margin must be non-negative, got {margin}")
+    rng = random.Random(seed)
+    solver = ExactSolver(tt_mb, max_empties=64, time_ms=0)
+    pos = Position.initial()
+    moves: List[int] = []
+    while not pos.terminal():
+        me, opp = solver._root(pos)
+        scored = []
+        for sq, flips in all_flips(me, opp):
+            move = 1 << sq
+            scored.append((-solver._negamax(opp ^ flips, me | flips | move, -MAX_SCORE, MAX_SCORE, depth - 1), sq))
+        # apply_move hands the turn to whoever can move, so there is always a move here
+        best = max(score for score, _ in scored)
+        sq = rng.choice([sq for score, sq in scored if score >= best - margin])
+        moves.append(sq)
+        pos = apply_move(pos, sq)
+    return moves
+
+
@@ -0,0 +1,180 @@
+from __future__ import annotations
+from typing import List, Tuple
+
+from .bitboard import popcount, shift_dir, BOARD_SIZES, Position
+
+# Full-board disc stability. A disc is stable along an axis (horizontal, vertical or
+# either diagonal) if the whole line through it is full, or if on one side it
+# touches the board edge or a stable disc of its own colour. Discs stable along all
+# four axes can never be flipped; we grow that set until it stops changing.
+
+RANK_1 = 0x00000000000000FF
+RANK_8 = 0xFF00000000000000
+
+Axis = Tuple[int, List[int], int]
+
+
+def _diagonals(step: int, size: int = 8) -> List[int]:
+    # step 9: squares with equal file-rank difference; step 7: equal file+rank sum
+    lines = {}
+    for r in range(size):
+        for c in range(size):
+            k = c - r if step == 9 else c + r
+            lines[k] = lines.get(k, 0) | (1 << (8 * r + c))
+    return list(lines.values())
+
+
+def _axes(size: int) -> Tuple[Axis, ...]:
+    # (direction, lines along that axis, squares walled off on that axis)
+    rank = (1 << size) - 1
+    ranks = [rank << (8 * r) for r in range(size)]
+    files = [sum(1 << (8 * r + c) for r in range(size)) for c in range(size)]
+    edge = ranks[0] | ranks[-1] | files[0] | files[-1]
+    return (
+        (1, ranks, files[0] | files[-1]),
+        (8, files, ranks[0] | ranks[-1]),
+        (9, _diagonals(9, size), edge),
+        (7, _diagonals(7, size), edge),
+    )
+
+
+AXES_BY_SIZE = {n: _axes(n) for n in BOARD_SIZES}
+AXES = AXES_BY_SIZE[8]
+
+
+def _edge_flips(own: int, opp: int, sq: int, n: int = 8) -> int:
+    # discs of `opp` flipped along an n-square line when `own` fills `sq`
+    flips = 0
+    for step in (1, -1):
+        run = 0
+        i = sq + step
+        while 0 <= i < n and (opp >> i) & 1:
+            run |= 1 << i
+            i += step
+        if run and 0 <= i < n and (own >> i) & 1:
+            flips |= run
+    return flips
+
+
+def _build_edge_table(n: int = 8) -> List[int]:
+    # For every (own, opp) edge occupancy: the `own` discs no sequence of edge moves
+    # can ever flip. Any empty square may be filled by either colour, since an edge
+    # square can be reached through a diagonal or perpendicular line too.
+    unstable = {}
+
+    def changed(a: int, b: int) -> int:
+        # squares whose colour can change from their state in (a, b)
+        key = (a, b)
+        if key in unstable:
+            return unstable[key]
+        out = 0
+        empty = ~(a | b) & full
+        for sq in range(n):
+            if not (empty >> sq) & 1:
+                continue
+            f = _edge_flips(a, b, sq, n)
+            out |= f | changed(a | f | (1 << sq), b & ~f)
+            f = _edge_flips(b, a, sq, n)
+            out |= f | changed(a & ~f, b | f | (1 << sq))
+        unstable[key] = out
+        return out
+
+    full = (1 << n) - 1
+    table = [0] * (1 << (2 * n))
+    for own in range(1 << n):
+        for opp in range(1 << n):
+            if own & opp == 0:
+                table[own << n | opp] = own & ~changed(own, opp)
+    return table
+
+
+# EDGE_TABLES[size][own << size | opp]: the never-flippable `own` discs of one edge
+EDGE_TABLES = {n: _build_edge_table(n) for n in BOARD_SIZES}
+EDGE_TABLE = EDGE_TABLES[8]
+
+
+def _file_bits(bb: int, col: int, size: int = 8) -> int:
+    out = 0
+    for r in range(size):
+        out |= ((bb >> (8 * r + col)) & 1) << r
+    return out
+
+
+def _file_mask(bits: int, col: int, size: int = 8) -> int:
+    out = 0
+    for r in range(size):
+        if (bits >> r) & 1:
+            out |= 1 << (8 * r + col)
+    return out
+
+
+def edge_stable_discs(own: int, opp: int, size: int = 8) -> int:
+    """Bitmask of `own` edge discs that can never be flipped, by table lookup per edge."""
+    table = EDGE_TABLES[size]
+    rank = (1 << size) - 1
+    top = 8 * (size - 1)
+    out = table[(own & rank) << size | (opp & rank)]
+    out |= table[((own >> top) & rank) << size | ((opp >> top) & rank)] << top
+    for col in (0, size - 1):
+        bits = table[_file_bits(own, col, size) << size | _file_bits(opp, col, size)]
+        out |= _file_mask(bits, col, size)
+    return out
+
+
+def edge_stability(me: int, opp: int, size: int = 8) -> int:
+    """Edge-stable disc differential from `me`'s point of view."""
+    return popcount(edge_stable_discs(me, opp, size)) - popcount(edge_stable_discs(opp, me, size))
+
+
+def _full_lines(occ: int, lines: List[int]) -> int:
+    full = 0
+    for line in lines:
+        if occ & line == line:
+            full |= line
+    return full
+
+
+def stable_discs(own: int, opp: int, size: int = 8) -> int:
+    """Bitmask of `own` discs that are stable under the definition above."""
+    occ = own | opp
+    anchored = [(d, _full_lines(occ, lines) | walls) for d, lines, walls in AXES_BY_SIZE[size]]
+    # edge discs only risk being flipped along their edge, so the table result is final
+    edge = edge_stable_discs(own, opp, size)
+    stable = edge
+    while True:
+        new = own
+        for d, fixed in anchored:
+            support = fixed | shift_dir(stable, d) | shift_dir(stable, -d)
+            new &= support
+        new |= edge
+        if new == stable:
+            return stable
+        stable = new
+
+
+def stable_mask(black: int, white: int, size: int = 8) -> Tuple[int, int]:
+    """Stable discs of each colour, e.g. for highlighting on the board."""
+    return stable_discs(black, white, size), stable_discs(white, black, size)
+
+
+def stability_full(black: int, white: int, size: int = 8) -> Tuple[int, int]:
+    """Stable disc counts for Black and White."""
+    b, w = stable_mask(black, white, size)
+    return popcount(b), popcount(w)
+
+
+def stable_count(own: int, opp: int, size: int = 8) -> int:
+    return popcount(stable_discs(own, opp, size))
+
+
+def stability_proxy(me: int, opp: int, size: int = 8) -> int:
+    """Stable-disc differential from `me`'s point of view."""
+    return stable_count(me, opp, size) - stable_count(opp, me, size)
+
+
+def stability_diff(pos: Position) -> int:
+    """Stable discs of the side to move minus the opponent's."""
+    b, w = stability_full(pos.black, pos.white, pos.size)
+    return b - w if pos.stm == 0 else w - b
+
+
@@ -0,0 +1,82 @@
+from __future__ import annotations
+from typing import List, Tuple
+
+from .bitboard import Position
+
+# The 8 symmetries of the board (dihedral group D4) acting on bitboards. Transform
+# k applies (k & 3) clockwise quarter turns, then a vertical flip if k & 4.
+# Colours and side to move are unchanged by a symmetry. Smaller boards sit in the
+# A1 corner, so a size argument keeps the transforms inside that size x size frame.
+
+MASK64 = 0xFFFFFFFFFFFFFFFF
+
+
+def flip_vertical(bb: int, size: int = 8) -> int:
+    """Mirror ranks: rank 1 <-> rank `size`."""
+    return int.from_bytes(bb.to_bytes(8, "little"), "big") >> 8 * (8 - size)
+
+
+def flip_diagonal(bb: int) -> int:
+    """Mirror across the A1-H8 diagonal."""
+    t = 0x0F0F0F0F00000000 & (bb ^ (bb << 28))
+    bb ^= t ^ (t >> 28)
+    t = 0x3333000033330000 & (bb ^ (bb << 14))
+    bb ^= t ^ (t >> 14)
+    t = 0x5500550055005500 & (bb ^ (bb << 7))
+    bb ^= t ^ (t >> 7)
+    return bb & MASK64
+
+
+def rotate90(bb: int, size: int = 8) -> int:
+    """Quarter turn clockwise as the board is drawn (A1 top-left)."""
+    return flip_diagonal(flip_vertical(bb, size))
+
+
+def transform(bb: int, k: int, size: int = 8) -> int:
+    for _ in range(k & 3):
+        bb = rotate90(bb, size)
+    if k & 4:
+        bb = flip_vertical(bb, size)
+    return bb
+
+
+def transform_square(sq: int, k: int, size: int = 8) -> int:
+    return transform(1 << sq, k, size).bit_length() - 1
+
+
+def _inverse(k: int) -> int:
+    probe = 0x0000000000000F03  # no symmetry of its own
+    return next(j for j in range(8) if transform(transform(probe, k), j) == probe)
+
+
+# INVERSE[k] undoes transform k
+INVERSE: Tuple[int, ...] = tuple(_inverse(k) for k in range(8))
+
+
+def transforms(bb: int, size: int = 8) -> List[int]:
+    return [transform(bb, k, size) for k in range(8)]
+
+
+def canonical_form(pos: Position) -> Tuple[Position, int]:
+    """Lexicographically smallest transformed (black, white) and the transform that produced it."""
+    best = None
+    best_k = 0
+    for k in range(8):
+        pair = (transform(pos.black, k, pos.size), transform(pos.white, k, pos.size))
+        if best is None or pair < best:
+            best, best_k = pair, k
+    return Position(best[0], best[1], pos.stm, pos.size), best_k
+
+
+def canonicalize(pos: Position) -> Position:
+    return canonical_form(pos)[0]
+
+
+def are_equivalent(a: Position, b: Position) -> bool:
+    """Whether one of the 8 symmetries maps `a` onto `b`, with the same side to move."""
+    if a.stm != b.stm or a.size != b.size:
+        return False
+    return any(transform(a.black, k, a.size) == b.black and transform(a.white, k, a.size) == b.white
+               for k in range(8))
+
+
@@ -10,8 +10,7 @@ from ..engine.eval import evaluate
 # Simple tree builder (width-limited) with scoring goals. Exports JSON and DOT.
 
 def legal_moves_list(pos: Position) -> List[int]:
-    lm = pos.legal_mask()
-    return [i for i in range(64) if (lm >> i) & 1]
+    return pos.legal_squares()
 
 
 def node_attrs(pos: Position) -> Dict:
@@ -82,8 +82,7 @@ class App:
 
     def compute_overlay(self):
         self.overlay_scores = {}
-        lm = self.pos.legal_mask()
-        moves = [i for i in range(64) if (lm >> i) & 1]
+        moves = self.pos.legal_squares()
         cfg = SearchConfig(max_depth=min(3, self.depth))
         for m in moves:
             child = self.pos.apply(m)
@@ -0,0 +1,58 @@
+import random
+
+from othello_coach.engine.bitboard import Position, apply_move, final_margin, flips_for_move, legal_moves, move_record
+from othello_coach.engine.openings import alg_to_sq
+
+
+def board(squares: str) -> int:
+    """Bitboard of space-separated squares, e.g. board("A1 H8")."""
+    return sum(1 << alg_to_sq(name) for name in squares.split())
+
+
+def brute_margin(me: int, opp: int, size: int = 8) -> int:
+    """Exact final margin for `me` to move by plain minimax, for checking the solver on tiny endgames."""
+    moves = legal_moves(me, opp, size)
+    if not moves:
+        if not legal_moves(opp, me, size):
+            return final_margin(me, opp, size)
+        return -brute_margin(opp, me, size)
+    best = -64
+    while moves:
+        move = moves & -moves
+        moves ^= move
+        flips = flips_for_move(me, opp, move)
+        best = max(best, -brute_margin(opp ^ flips, me | flips | move, size))
+    return best
+
+
+def ever_flipped(me: int, opp: int, size: int = 8, seen=None) -> int:
+    """Squares whose disc changes colour along some line of play from here, by exhaustive search."""
+    if seen is None:
+        seen = {}
+    key = (me, opp)
+    if key in seen:
+        return seen[key]
+    moves = legal_moves(me, opp, size)
+    out = 0
+    if not moves:
+        if legal_moves(opp, me, size):
+            out = ever_flipped(opp, me, size, seen)
+    while moves:
+        move = moves & -moves
+        moves ^= move
+        flips = flips_for_move(me, opp, move)
+        out |= flips | ever_flipped(opp ^ flips, me | flips | move, size, seen)
+    seen[key] = out
+    return out
+
+
+def random_game(seed, size=8):
+    """(position, square, flips) for every move of a random game, plus the final position."""
+    rng = random.Random(seed)
+    pos = Position.initial(size)
+    moves = []
+    while not pos.terminal():
+        sq = rng.choice(pos.legal_squares())  # apply_move always hands over a side that can move
+        moves.append((pos,) + move_record(pos, sq))
+        pos = apply_move(pos, sq)
+    return moves, pos
@@ -0,0 +1,95 @@
+import pytest
+
+from helpers import random_game
+from othello_coach.engine.bitboard import (
+    BOARD_MASKS, PASS, ZOBRIST_BLACK_TO_MOVE, KernelError, Position, apply_move, apply_move_batch, count_empties,
+    final_score, is_game_over, pack_position, random_position, unpack_position, zobrist,
+)
+
+POSITIONS = [random_position(empties, seed) for empties in (0, 10, 30, 60) for seed in range(3)]
+
+
+def test_pack_round_trips():
+    for pos in POSITIONS + [pos for pos, _, _ in random_game(2)[0]]:
+        token = pack_position(pos)
+        assert 0 <= token < 1 << 129
+        assert unpack_position(token) == pos
+    start = Position.initial()
+    assert pack_position(start) == start.black | start.white << 64
+    assert pack_position(start.pass_move()) == pack_position(start) | 1 << 128
+
+
+@pytest.mark.parametrize("token, error", [
+    (-1, ValueError),
+    (1 << 129, ValueError),      # past the stm bit
+    (1 << 64 | 1, KernelError),  # A1 both colours
+])
+def test_unpack_rejects_bad_tokens(token, error):
+    with pytest.raises(error):
+        unpack_position(token)
+
+
+def test_game_over_and_final_score_on_known_boards():
+    full = (1 << 64) - 1
+    left = 0x0F0F0F0F0F0F0F0F  # files A-D
+    assert is_game_over(left, full ^ left) and final_score(left, full ^ left) == 0
+    assert final_score(left | 1 << 4, full ^ left ^ 1 << 4) == 2  # Black also has E1
+    # wipeout: the winner gets every empty square
+    assert is_game_over(0x3C3C00000000, 0) and final_score(0x3C3C00000000, 0) == 64
+    assert final_score(0, 0x3C3C00000000) == -64
+    # A1-C1 against G8-H8: neither side can move
+    assert is_game_over(0b111, 3 << 62) and final_score(0b111, 3 << 62) == 3 - 2 + 59
+    assert final_score(3 << 62, 0b111) == -60
+    start = Position.initial()
+    assert not is_game_over(start.black, start.white)
+    # a full 6x6 board of one colour
+    assert is_game_over(BOARD_MASKS[6], 0, 6) and final_score(BOARD_MASKS[6], 0, 6) == 36
+
+
+def test_zobrist_is_the_position_hash():
+    for pos in POSITIONS:
+        assert zobrist(pos.black, pos.white, pos.stm) == pos.hash64()
+        # the side to move is part of the key
+        assert zobrist(pos.black, pos.white, 1 - pos.stm) == pos.hash64() ^ ZOBRIST_BLACK_TO_MOVE
+        # and the colours are: swapping them is a different position
+        assert zobrist(pos.white, pos.black, pos.stm) != pos.hash64()
+    assert zobrist(0, 0, 1) == 0
+    # pinned: stored database keys depend on this value
+    assert Position.initial().hash64() == 0x44926A6021DDC41B
+
+
+@pytest.mark.parametrize("size", [6, 8])
+def test_random_position_is_reproducible_with_the_asked_empties(size):
+    most = size * size - 4
+    for empties in (0, 1, most // 2, most):
+        positions = [random_position(empties, seed, size) for seed in range(4)]
+        assert positions == [random_position(empties, seed, size) for seed in range(4)]
+        for pos in positions:
+            assert pos.size == size
+            assert count_empties(pos.black, pos.white, size) == empties
+    assert random_position(most, 0, size) == Position.initial(size)
+    assert len({random_position(most // 2, seed, size) for seed in range(4)}) > 1
+    for empties in (-1, most + 1):
+        with pytest.raises(ValueError, match="Empties out of range"):
+            random_position(empties, 0, size)
+
+
+def test_apply_move_batch_matches_apply_move():
+    pairs = [(pos, sq) for pos in POSITIONS for sq in (pos.legal_squares()[:2] + [0, 27, PASS])]
+    rows = (
+        [pos.black for pos, _ in pairs], [pos.white for pos, _ in pairs], [pos.stm for pos, _ in pairs],
+        [sq for _, sq in pairs],
+    )
+    expected = ([], [], [], [])
+    for pos, sq in pairs:
+        try:
+            nxt, legal = apply_move(pos, sq), True
+        except ValueError:
+            nxt, legal = pos, False
+        for column, value in zip(expected, (nxt.black, nxt.white, nxt.stm, legal)):
+            column.append(value)
+    assert True in expected[3] and False in expected[3]
+    assert apply_move_batch(*rows) == expected
+    assert apply_move_batch(*rows, workers=2) == expected
+    with pytest.raises(ValueError):
+        apply_move_batch([0], [0], [0], [])
@@ -0,0 +1,76 @@
+import pytest
+
+from othello_coach.engine.bitboard import (
+    KernelError, Position, flip_by_direction, flips_for_move, move_result, play_move, random_position, undo_move,
+    validate_board,
+)
+from othello_coach.engine.solver import solve_exact
+
+
+def test_kernel_error_is_a_value_error():
+    assert issubclass(KernelError, ValueError)
+
+
+@pytest.mark.parametrize("sq", [-1, 64, 100])
+def test_play_move_rejects_off_board_squares(sq):
+    pos = Position.initial()
+    with pytest.raises(KernelError):
+        play_move(pos.black, pos.white, sq)
+    with pytest.raises(KernelError):
+        move_result(pos, sq)
+    with pytest.raises(KernelError):
+        flip_by_direction(pos, sq)
+
+
+@pytest.mark.parametrize("move", [0, 1 << 64, (1 << 19) | (1 << 26)])
+def test_flips_for_move_rejects_anything_but_one_square(move):
+    # no move, off the board, and D3 plus C4 at once
+    pos = Position.initial()
+    with pytest.raises(KernelError, match="single on-board bit"):
+        flips_for_move(pos.black, pos.white, move)
+
+
+def test_play_move_rejects_squares_off_a_small_board():
+    pos = Position.initial(6)
+    with pytest.raises(KernelError):
+        play_move(pos.black, pos.white, 6, size=6)  # G1
+
+
+def test_occupied_square_is_an_illegal_move_not_a_flip():
+    pos = Position.initial()
+    with pytest.raises(ValueError, match="Illegal move") as exc:
+        play_move(pos.black, pos.white, 27)  # D4
+    assert not isinstance(exc.value, KernelError)
+
+
+def test_overlapping_boards_are_rejected():
+    with pytest.raises(KernelError, match="Overlapping"):
+        Position(1 << 27, (1 << 27) | (1 << 28), 0)
+    with pytest.raises(KernelError):
+        validate_board(3, 1)
+
+
+def test_bad_masks_and_side_to_move_are_rejected():
+    with pytest.raises(KernelError):
+        Position(-1, 0, 0)
+    with pytest.raises(KernelError):
+        Position(1 << 64, 0, 0)
+    with pytest.raises(KernelError):
+        Position(1 << 7, 0, 0, size=6)  # H1 is off the 6x6 board
+    with pytest.raises(KernelError):
+        Position(0, 0, 2)
+
+
+def test_undo_move_rejects_off_board_squares():
+    with pytest.raises(KernelError):
+        undo_move(Position.initial(), 65, 1)
+
+
+def test_over_cap_empties_raise_instead_of_scoring_zero():
+    with pytest.raises(KernelError, match="exceeds solver cap"):
+        solve_exact(random_position(12, 0), max_empties=11)
+
+
+def test_exhausted_budget_raises_instead_of_scoring_zero():
+    with pytest.raises(TimeoutError):
+        solve_exact(random_position(12, 0), max_nodes=10)
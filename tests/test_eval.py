@@ -0,0 +1,220 @@
+import pytest
+
+from helpers import board
+from othello_coach.engine.bitboard import Position, apply_move, final_margin, random_position
+from othello_coach.engine.eval import (
+    CORNER_ACCESS, CORNER_DANGER, CORNER_DANGER_BY_SIZE, CORNER_OWNED, EDGE_FULL_BONUS, ENDGAME_MAX_EMPTIES,
+    FEATURE_COUNT, FEAT_CORNERS, FEAT_DISCS, FEAT_EMPTIES, FEAT_FRONTIER, FEAT_MOBILITY, FEAT_POT_MOBILITY,
+    FEAT_STABILITY, OPENING_MIN_EMPTIES, PHASE_ENDGAME, PHASE_MIDGAME, PHASE_OPENING, POS_CORNER_MOVE,
+    POS_CORNER_THREAT, POS_GAME_OVER, POS_ODD_REGION, SQUARE_WEIGHTS, EvalWeights, best_move_heuristic,
+    corner_adjacent_penalty, corner_eval, edge_control, evaluate, evaluate_symmetric, feature_vector,
+    frontier_counts, game_phase, interior_counts, linear_eval, mobility_diff, position_flags, positional_score,
+    potential_mobility_split, safe_mobility,
+)
+from othello_coach.engine.openings import alg_to_sq
+from othello_coach.engine.solver import DEFAULT_MAX_EMPTIES, check_empties, solve_exact
+from othello_coach.engine.symmetry import transform
+
+POSITIONS = [random_position(empties, seed) for empties in (8, 20, 32, 44) for seed in range(10)]
+
+
+def test_symmetric_table_makes_evaluate_symmetric_a_no_op():
+    # every built-in term and SQUARE_WEIGHTS are symmetric, so averaging changes nothing
+    for pos in POSITIONS:
+        assert evaluate_symmetric(pos) == evaluate(pos)
+        assert evaluate_symmetric(pos, squares=SQUARE_WEIGHTS) == evaluate(pos, squares=SQUARE_WEIGHTS)
+
+
+def test_asymmetric_table_is_averaged_out():
+    # only A1 is worth anything; of the 8 copies, the two that fix A1 (identity and
+    # the A1-H8 mirror) keep the disc there, so it averages 2 * 800 / 8
+    lopsided = [800] + [0] * 63
+    pos = Position(1 << 0 | 0x0000000810000000, 0x0000001008000000, 0)
+    assert evaluate(pos, squares=lopsided) - evaluate(pos) == 800
+    assert evaluate_symmetric(pos, squares=lopsided) - evaluate_symmetric(pos) == 200
+    differs = 0
+    for pos in POSITIONS:
+        plain = evaluate(pos, squares=lopsided)
+        assert evaluate_symmetric(pos, squares=lopsided) == sum(
+            evaluate(Position(transform(pos.black, k), transform(pos.white, k), pos.stm), squares=lopsided)
+            for k in range(8)) // 8
+        differs += evaluate_symmetric(pos, squares=lopsided) != plain
+    assert differs
+
+
+def test_corner_adjacent_penalty_is_symmetric():
+    for pos in POSITIONS:
+        expected = corner_adjacent_penalty(pos.black, pos.white)
+        for k in range(8):
+            assert corner_adjacent_penalty(transform(pos.black, k), transform(pos.white, k)) == expected
+
+
+def test_corner_adjacent_penalty_counts_x_and_c_squares_only():
+    for corner, squares in CORNER_DANGER:
+        assert corner_adjacent_penalty(1 << corner, 0) == 0
+        assert corner_adjacent_penalty(squares, 0) == 3
+        assert corner_adjacent_penalty(0, squares) == -3
+
+
+def test_corner_danger_squares_are_the_corner_neighbours():
+    assert CORNER_DANGER_BY_SIZE[8] == CORNER_DANGER
+
+
+def test_evaluate_is_from_the_side_to_move():
+    pos = random_position(30, 3)
+    assert evaluate(Position(pos.white, pos.black, 1 - pos.stm)) == evaluate(pos)
+
+
+def test_game_phase_boundaries():
+    for empties, phase in ((60, PHASE_OPENING), (OPENING_MIN_EMPTIES, PHASE_OPENING),
+                           (OPENING_MIN_EMPTIES - 1, PHASE_MIDGAME), (ENDGAME_MAX_EMPTIES + 1, PHASE_MIDGAME),
+                           (ENDGAME_MAX_EMPTIES, PHASE_ENDGAME), (0, PHASE_ENDGAME)):
+        pos = random_position(empties, empties)
+        assert game_phase(pos.black, pos.white) == phase, empties
+
+
+def test_endgame_phase_is_within_the_solver_cap():
+    assert ENDGAME_MAX_EMPTIES == DEFAULT_MAX_EMPTIES
+    pos = random_position(ENDGAME_MAX_EMPTIES, 1)
+    assert game_phase(pos.black, pos.white) == PHASE_ENDGAME
+    check_empties(pos, DEFAULT_MAX_EMPTIES)
+
+
+def test_heuristic_move_is_legal():
+    for pos in POSITIONS:
+        sq = best_move_heuristic(pos)
+        if not pos.legal_squares():
+            assert sq is None
+            continue
+        assert pos.is_legal(sq)
+    # from the start every move is the same up to symmetry, and equal scores take the lowest square
+    assert best_move_heuristic(Position.initial()) == min(Position.initial().legal_squares())
+
+
+def test_heuristic_agrees_with_the_solver_when_every_move_ends_the_game():
+    checked = 0
+    for seed in range(40):
+        pos = random_position(1, seed)
+        if not pos.legal_squares():
+            continue
+        sq = best_move_heuristic(pos)
+        final = apply_move(pos, sq)
+        assert final.terminal()
+        me, opp = (final.black, final.white) if pos.stm == 0 else (final.white, final.black)
+        assert final_margin(me, opp) == solve_exact(pos)
+        checked += 1
+    assert checked >= 10
+
+
+RANK_1 = 0xFF
+FULL = (1 << 64) - 1
+
+
+def test_frontier_counts_on_known_boards():
+    start = Position.initial()
+    assert frontier_counts(start.black, start.white) == (2, 2)
+    # rank 1 walled in by rank 2 has no empty neighbour left; rank 2 borders rank 3
+    assert frontier_counts(RANK_1, RANK_1 << 8) == (0, 8)
+    assert interior_counts(RANK_1, RANK_1 << 8) == (8, 0)
+    # only A5 is empty: its five neighbours, but not H4 or H5 across the board edge
+    assert frontier_counts(FULL & ~board("A5"), 0) == (5, 0)
+
+
+def test_stability_term_on_a_known_board():
+    assert evaluate(Position.initial()) == 0
+    # Black owns rank 1 outright: eight stable discs, weighted by the stability term alone
+    only_stability = EvalWeights(mobility=0, pot_mobility=0, corners=0, corner_adj_penalty=0, frontier=0,
+                                 stability=1, disc_diff=0)
+    assert evaluate(Position(RANK_1, board("D4"), 0), only_stability) == 8
+    assert evaluate(Position(RANK_1, board("D4"), 1), only_stability) == -8
+
+
+def test_positional_score_on_a_known_board():
+    # A1 (100) and B2 (-50) against H8 (100)
+    assert positional_score(board("A1 B2"), board("H8")) == -50
+    assert positional_score(board("H8"), board("A1 B2")) == 50
+    assert positional_score(board("A1"), 0, [0] * 63 + [7]) == 0
+    assert positional_score(board("H8"), 0, [0] * 63 + [7]) == 7
+    with pytest.raises(ValueError, match="Expected 64 square weights"):
+        positional_score(board("A1"), 0, [1] * 60)
+
+
+def test_mobility_diff_on_known_boards():
+    start = Position.initial()
+    assert mobility_diff(start.black, start.white) == 0
+    # Black A1 can flank B1-C1 from D1; White has nothing to flank A1 against
+    assert mobility_diff(board("A1"), board("B1 C1")) == 1
+    assert mobility_diff(board("B1 C1"), board("A1")) == -1
+
+
+def test_feature_vector_on_known_boards():
+    assert feature_vector(Position.initial()) == [0, 0, 0, 0, 0, 0, 60]
+    # Black owns rank 1, White has A2; Black to move
+    features = feature_vector(Position(RANK_1, board("A2"), 0))
+    assert features[FEAT_MOBILITY] == 1         # Black's A3; White has no move
+    assert features[FEAT_POT_MOBILITY] == 3 - 7  # A3, B2, B3 next to White; B2-H2 next to Black
+    assert features[FEAT_STABILITY] == 8        # A2 falls to A3
+    assert features[FEAT_FRONTIER] == 8 - 1
+    assert features[FEAT_CORNERS] == 2
+    assert features[FEAT_DISCS] == 7
+    assert features[FEAT_EMPTIES] == 55
+
+
+def test_corner_eval_on_a_known_board():
+    # Black holds A1; White could take H8 across Black's G7
+    pos = Position(board("A1 G7"), board("F6"), 0)
+    assert corner_eval(pos) == CORNER_OWNED - CORNER_ACCESS
+    # from White's side A1 counts against it, and Black has no corner to take
+    assert corner_eval(pos.pass_move()) == -CORNER_OWNED
+
+
+def test_safe_mobility_drops_a_move_that_concedes_a_corner():
+    # Black E5 between White D4 and F6: C3 is safe, but G7 opens H8 to White along the diagonal
+    pos = Position(board("E5"), board("D4 F6"), 0)
+    assert sorted(pos.legal_squares()) == [alg_to_sq("C3"), alg_to_sq("G7")]
+    assert mobility_diff(*pos.me_opp()) == 2
+    assert safe_mobility(pos) == 1
+    assert safe_mobility(pos.pass_move()) == -1
+
+
+def test_position_flags_on_known_boards():
+    assert position_flags(Position.initial()) == 0  # one even region of 60
+    pos = Position(board("E5"), board("D4 F6"), 0)
+    assert position_flags(pos) == POS_ODD_REGION
+    # after G7 White can take H8 along the long diagonal
+    after = pos.apply(alg_to_sq("G7"))
+    assert position_flags(after) & (POS_CORNER_MOVE | POS_CORNER_THREAT) == POS_CORNER_MOVE
+    assert position_flags(after.pass_move()) & (POS_CORNER_MOVE | POS_CORNER_THREAT) == POS_CORNER_THREAT
+    # A1-C1 against G8-H8: nobody can move, with 59 squares in one region
+    assert position_flags(Position(board("A1 B1 C1"), board("G8 H8"), 0)) == POS_GAME_OVER | POS_ODD_REGION
+
+
+def test_linear_eval_weights_pick_out_features():
+    pos = Position(RANK_1, board("A2"), 0)
+    features = feature_vector(pos)
+    for i in range(FEATURE_COUNT):
+        one_hot = [0] * FEATURE_COUNT
+        one_hot[i] = 1
+        assert linear_eval(pos, one_hot) == features[i]
+    assert linear_eval(pos, [1, 0, 0, 0, 0, 0, 0]) == mobility_diff(RANK_1, board("A2")) == 1
+    assert linear_eval(pos, [10, 1, 0, 0, 0, 0, -1]) == 10 - 4 - 55
+    with pytest.raises(ValueError, match="Expected 7 feature weights"):
+        linear_eval(pos, [1] * 6)
+
+
+def test_potential_mobility_split_on_known_boards():
+    start = Position.initial()
+    # five empty neighbours around each of the other colour's two discs
+    assert potential_mobility_split(start.black, start.white) == (10, 10)
+    # H4 and A5 touch only across the board edge, which doesn't count
+    assert potential_mobility_split(board("H4"), board("A5")) == (5, 5)
+    assert potential_mobility_split(RANK_1, board("A2")) == (3, 7)
+
+
+def test_edge_control_on_a_known_board():
+    # Black owns all of rank 1, White holds A8
+    pos = Position(RANK_1, board("A8"), 0)
+    #                             rank 1            rank 8  file A   file H
+    assert edge_control(pos) == (8 + EDGE_FULL_BONUS) - 1 + (1 - 1) + 1
+    assert edge_control(pos.pass_move()) == -edge_control(pos)
+    assert edge_control(Position.initial()) == 0
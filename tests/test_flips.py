@@ -0,0 +1,49 @@
+import random
+
+from othello_coach.engine.bitboard import flips_fill, flips_for_move
+
+STEPS = [(dr, dc) for dr in (-1, 0, 1) for dc in (-1, 0, 1) if dr or dc]
+
+
+def reference_flips(me: int, opp: int, sq: int) -> int:
+    """One square at a time with explicit bounds checks, as the original move generator did."""
+    flips = 0
+    r0, c0 = divmod(sq, 8)
+    for dr, dc in STEPS:
+        run = 0
+        r, c = r0 + dr, c0 + dc
+        while 0 <= r < 8 and 0 <= c < 8 and (opp >> (8 * r + c)) & 1:
+            run |= 1 << (8 * r + c)
+            r, c = r + dr, c + dc
+        if run and 0 <= r < 8 and 0 <= c < 8 and (me >> (8 * r + c)) & 1:
+            flips |= run
+    return flips
+
+
+def random_triples(n: int, seed: int):
+    rng = random.Random(seed)
+    for i in range(n):
+        sq = i % 64  # every square equally often
+        occupied = rng.getrandbits(64) | rng.getrandbits(64)  # about 3/4 full
+        me = occupied & rng.getrandbits(64) & ~(1 << sq)
+        opp = occupied & ~me & ~(1 << sq)
+        yield me, opp, sq
+
+
+def test_ray_flips_match_reference():
+    for me, opp, sq in random_triples(20000, 311):
+        assert flips_for_move(me, opp, 1 << sq) == reference_flips(me, opp, sq), (hex(me), hex(opp), sq)
+
+
+def test_fill_flips_match_ray_flips():
+    for me, opp, sq in random_triples(20000, 349):
+        assert flips_fill(me, opp, 1 << sq) == flips_for_move(me, opp, 1 << sq), (hex(me), hex(opp), sq)
+
+
+def test_fill_flips_on_crowded_boards():
+    # long runs up to the edges, where a fill that wraps or overshoots would pick up extra discs
+    full = (1 << 64) - 1
+    for sq in range(64):
+        anchor = 1 << (63 - sq)  # the opposite square, never sq itself
+        for me, opp in ((0, full & ~(1 << sq)), (anchor, full & ~(1 << sq) & ~anchor)):
+            assert flips_fill(me, opp, 1 << sq) == reference_flips(me, opp, sq), (hex(me), hex(opp), sq)
@@ -0,0 +1,99 @@
+import pytest
+
+from helpers import random_game
+from othello_coach.engine.bitboard import (
+    PASS, KernelError, Position, apply_move, apply_move_hashed, move_record, undo_move,
+)
+
+
+def take_back(pos, prev, sq, flips):
+    # apply_move folds a forced pass into the move, so take that back first
+    if pos.stm == prev.stm:
+        pos = undo_move(pos, PASS, 0)
+    return undo_move(pos, sq, flips)
+
+
+# games 2 and 3 include forced passes
+@pytest.mark.parametrize("seed", range(4))
+def test_incremental_hash_matches_a_full_recompute_forwards_and_back(seed):
+    moves, final = random_game(seed)
+    h = Position.initial().hash64()
+    hashes = []
+    for prev, sq, _ in moves:
+        hashes.append(h)
+        nxt, h = apply_move_hashed(prev, sq, h)
+        assert h == nxt.hash64()
+    assert h == final.hash64()
+    pos = final
+    for (prev, sq, flips), prev_hash in zip(reversed(moves), reversed(hashes)):
+        pos = take_back(pos, prev, sq, flips)
+        assert pos.hash64() == prev_hash
+
+
+def test_incremental_hash_of_a_pass():
+    # White holds both corners of rank 1, so Black must pass
+    pos = Position((1 << 1) | (1 << 6), (1 << 0) | (1 << 7), 0)
+    nxt, h = apply_move_hashed(pos, PASS, pos.hash64())
+    assert nxt == pos.pass_move() and h == nxt.hash64()
+
+
+@pytest.mark.parametrize("size, seed", [(8, 0), (8, 2), (6, 0), (6, 1)])
+def test_undo_restores_every_position_of_a_game(size, seed):
+    moves, final = random_game(seed, size)
+    for prev, sq, flips in moves:
+        assert undo_move(prev.apply(sq), sq, flips) == prev
+    pos = final
+    for prev, sq, flips in reversed(moves):
+        pos = take_back(pos, prev, sq, flips)
+        assert pos == prev
+    assert pos == Position.initial(size)
+
+
+def test_undo_rejects_a_record_that_does_not_match():
+    pos = Position.initial()
+    sq, flips = move_record(pos, 19)  # D3 flips D4
+    nxt = pos.apply(sq)
+    with pytest.raises(ValueError, match="does not match"):
+        undo_move(nxt, 20, flips)     # E3 is empty
+    with pytest.raises(ValueError, match="does not match"):
+        undo_move(nxt, sq, 1 << 36)   # E5 is White's, not the mover's
+    with pytest.raises(ValueError, match="flips nothing"):
+        undo_move(nxt, PASS, flips)
+    with pytest.raises(ValueError, match="legal moves available"):
+        move_record(pos, PASS)
+
+
+@pytest.mark.parametrize("seed", range(4))
+def test_apply_move_hands_the_turn_to_the_side_that_plays_next(seed):
+    moves, final = random_game(seed)
+    passes = 0
+    for prev, sq, _ in moves:
+        nxt, plain = apply_move(prev, sq), prev.apply(sq)
+        if plain.legal_mask() == 0 and not plain.terminal():
+            assert nxt == plain.pass_move() and nxt.stm == prev.stm
+            passes += 1
+        else:
+            assert nxt == plain
+        assert nxt.legal_mask() or nxt.terminal()
+    assert (passes > 0) == (seed >= 2)
+    assert final.terminal()
+
+
+def test_apply_move_rejects_illegal_squares():
+    pos = Position.initial()
+    for sq in (0, 27, 18):  # A1 flanks nothing, D4 is occupied, C3 only sees White on D4-E5
+        with pytest.raises(ValueError, match="Illegal move"):
+            apply_move(pos, sq)
+    with pytest.raises(KernelError):
+        apply_move(pos, 65)
+
+
+def test_explicit_pass_only_when_the_side_to_move_is_stuck():
+    # White holds both corners of rank 1, so Black must pass and White can still play
+    stuck = Position((1 << 1) | (1 << 6), (1 << 0) | (1 << 7), 0)
+    assert apply_move(stuck, PASS) == Position(stuck.black, stuck.white, 1)
+    with pytest.raises(ValueError, match="legal moves available"):
+        apply_move(Position.initial(), PASS)
+    # A1-C1 against G8-H8: nobody can move
+    with pytest.raises(ValueError, match="game is over"):
+        apply_move(Position(0b111, (1 << 62) | (1 << 63), 0), PASS)
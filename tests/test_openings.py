@@ -0,0 +1,68 @@
+import pytest
+
+from helpers import random_game
+from othello_coach.engine.bitboard import Position, apply_move
+from othello_coach.engine.openings import BOOK_LINES, alg_to_sq, book_move, replay_transcript, sq_to_alg
+from othello_coach.engine.symmetry import are_equivalent, transform
+
+
+def _transformed(pos, k):
+    return Position(transform(pos.black, k), transform(pos.white, k), pos.stm, pos.size)
+
+
+def _play(line):
+    pos = Position.initial()
+    for tok in line.split():
+        pos = apply_move(pos, alg_to_sq(tok))
+    return pos
+
+
+def test_book_follows_its_lines():
+    assert book_move(_play("F5")) == alg_to_sq("D6")
+    assert book_move(_play("F5 F6")) == alg_to_sq("E6")
+    assert book_move(_play("F5 D6 C3 D3 C4 F4 C5 B3 C2")) is None
+
+
+@pytest.mark.parametrize("k", range(8))
+def test_mirrored_lines_get_the_mirrored_reply(k):
+    for line in BOOK_LINES:
+        pos = Position.initial()
+        for tok in line.split():
+            mirrored = _transformed(pos, k)
+            reply = book_move(mirrored)
+            assert mirrored.is_legal(reply)
+            if line == BOOK_LINES[0]:
+                assert book_move(pos) == alg_to_sq(tok)
+            # the start position is symmetric itself, so compare the positions the replies reach
+            assert are_equivalent(apply_move(mirrored, reply), apply_move(pos, book_move(pos)))
+            pos = apply_move(pos, alg_to_sq(tok))
+
+
+def test_every_first_move_is_answered():
+    # the four first moves are one opening under symmetry
+    replies = {}
+    for sq in Position.initial().legal_squares():
+        pos = apply_move(Position.initial(), sq)
+        replies[sq] = book_move(pos)
+        assert pos.is_legal(replies[sq])
+    assert replies[alg_to_sq("F5")] == alg_to_sq("D6")
+    assert replies[alg_to_sq("D3")] == alg_to_sq("C5")
+
+
+@pytest.mark.parametrize("seed", range(4))
+def test_transcript_replays_a_whole_game(seed):
+    # games 2 and 3 include forced passes, which transcripts leave out
+    moves, final = random_game(seed)
+    assert replay_transcript("".join(sq_to_alg(sq).lower() for _, sq, _ in moves)) == final
+    assert replay_transcript(" ".join(sq_to_alg(sq) for _, sq, _ in moves[:4])) == moves[4][0]
+
+
+@pytest.mark.parametrize("transcript, error", [
+    ("f5d6c3z9", r"Move 3 \(z9\)"),
+    ("f5d6c3d3c4f4c5b3c2a1", r"Move 9 \(a1\): Illegal move"),
+    ("f5d6d6", r"Move 2 \(d6\): Illegal move"),
+    ("f5d", "odd number of characters"),
+])
+def test_transcript_errors_name_the_bad_move(transcript, error):
+    with pytest.raises(ValueError, match=error):
+        replay_transcript(transcript)
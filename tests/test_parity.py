@@ -0,0 +1,35 @@
+import pytest
+
+from helpers import board
+from othello_coach.engine.bitboard import Position
+from othello_coach.engine.parity import BLACK, NEUTRAL, WHITE, parity_score, region_last_mover
+
+FULL = (1 << 64) - 1
+# Black everywhere but A1 and H8, which sit behind White's B1 and G8: two odd
+# regions of one square, both open to Black only
+CORNERS_BLACK = FULL & ~board("A1 B1 G8 H8")
+CORNERS_WHITE = board("B1 G8")
+
+
+def test_parity_score_counts_odd_regions_each_side_can_enter():
+    assert parity_score(Position.initial()) == 0  # one even region
+    assert parity_score(Position(CORNERS_BLACK, CORNERS_WHITE, 0)) == 2
+    assert parity_score(Position(CORNERS_BLACK, CORNERS_WHITE, 1)) == -2
+    # fill H8 and only A1 is left; empty A2 instead and the A1-A2 region is even
+    assert parity_score(Position(CORNERS_BLACK | board("H8"), CORNERS_WHITE, 0)) == 1
+    assert parity_score(Position(CORNERS_BLACK & ~board("A2"), CORNERS_WHITE, 0)) == 1
+
+
+def test_region_last_mover_on_known_regions():
+    # only Black can enter A1, so Black plays it whoever is to move
+    assert region_last_mover(board("A1"), CORNERS_BLACK, CORNERS_WHITE, BLACK) == BLACK
+    assert region_last_mover(board("A1"), CORNERS_BLACK, CORNERS_WHITE, WHITE) == BLACK
+    # the side to move starts the even region of the opening, so the other side ends it
+    start = Position.initial()
+    empty = FULL & ~(start.black | start.white)
+    assert region_last_mover(empty, start.black, start.white, BLACK) == WHITE
+    assert region_last_mover(empty, start.black, start.white, WHITE) == BLACK
+    # A1-C1 against G8-H8: nobody can enter
+    assert region_last_mover(FULL & ~board("A1 B1 C1 G8 H8"), board("A1 B1 C1"), board("G8 H8"), BLACK) == NEUTRAL
+    with pytest.raises(ValueError, match="Side to move"):
+        region_last_mover(board("A1"), CORNERS_BLACK, CORNERS_WHITE, 2)
@@ -0,0 +1,40 @@
+import pytest
+
+from othello_coach.engine.bitboard import Position, legal_moves, perft
+
+
+@pytest.mark.parametrize("depth, nodes", [(1, 4), (2, 12), (3, 56), (4, 244), (5, 1396), (6, 8200)])
+def test_perft_from_start(depth, nodes):
+    assert perft(Position.initial(), depth) == nodes
+
+
+def test_perft_depth_zero_is_one():
+    assert perft(Position.initial(), 0) == 1
+
+
+def test_perft_single_capture_ends_game():
+    # Black A1, White B1: C1 is the only move and wipes White out
+    pos = Position(1 << 0, 1 << 1, 0)
+    assert pos.legal_squares() == [2]
+    assert [perft(pos, d) for d in range(4)] == [1, 1, 1, 1]
+
+
+def test_perft_forced_pass_is_a_ply():
+    # White holds both corners of rank 1, so Black must pass; White then has C1 and F1
+    pos = Position((1 << 1) | (1 << 6), (1 << 0) | (1 << 7), 0)
+    assert pos.legal_squares() == []
+    assert [perft(pos, d) for d in range(6)] == [1, 1, 2, 2, 2, 2]
+
+
+def test_perft_finished_game_is_a_leaf():
+    pos = Position(1 << 0, 1 << 63, 0)
+    assert perft(pos, 5) == 1
+
+
+def test_rays_do_not_wrap_across_ranks():
+    # H1 and A2 are adjacent bits but not adjacent squares
+    assert legal_moves(1 << 7, 1 << 8) == 0
+    assert legal_moves(1 << 8, 1 << 7) == 0
+    # H1 -> G2 -> F3 is a real diagonal; H2 -> A3 is not a line
+    assert legal_moves(1 << 7, 1 << 14) == 1 << 21
+    assert legal_moves(1 << 15, 1 << 16) == 0
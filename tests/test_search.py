@@ -0,0 +1,39 @@
+import pytest
+
+from othello_coach.engine.bitboard import Position, random_position
+from othello_coach.engine.eval import DISC_SCALE
+from othello_coach.engine.search import SearchConfig, Searcher
+from othello_coach.engine.solver import solve_exact
+
+
+def test_wipeout_scores_every_square():
+    pos = Position(0x3C3C00000000 | 0x1818, 0, 1)  # 10 Black discs, White to move
+    a = Searcher().search(pos, SearchConfig(max_depth=4))
+    assert (a.best_move, a.score, a.nodes) == (None, -6400, 1)
+    a = Searcher().search(Position(pos.black, 0, 0), SearchConfig(max_depth=4))
+    assert a.score == 6400
+
+
+@pytest.mark.parametrize("stm, score", [(0, 6000), (1, -6000)])
+def test_mid_board_double_pass_returns_the_disc_margin_at_once(stm, score):
+    # A1-C1 against G8-H8: neither side can move with 59 squares empty
+    pos = Position(0b111, (1 << 62) | (1 << 63), stm)
+    a = Searcher().search(pos, SearchConfig(max_depth=6))
+    assert (a.best_move, a.score, a.nodes) == (None, score, 1)
+
+
+def test_pass_into_a_blocked_board_stops_at_the_double_pass():
+    # Black must pass; White's only reply, C1, leaves a board neither side can move on
+    pos = Position((1 << 1) | (1 << 63), 1 << 0, 0)
+    a = Searcher().search(pos, SearchConfig(max_depth=6))
+    assert (a.best_move, a.score) == (64, -6200)
+    # depth 1 stops after the pass; deeper iterations visit the pass, C1 and the finished game
+    assert a.nodes == 2 + 3 * 5
+
+
+@pytest.mark.parametrize("seed", range(4))
+def test_searching_to_the_end_matches_the_solver_scale(seed):
+    # passes use up depth here, so allow one per empty square
+    pos = random_position(6, seed)
+    a = Searcher().search(pos, SearchConfig(max_depth=12))
+    assert a.score == solve_exact(pos) * DISC_SCALE
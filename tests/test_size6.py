@@ -0,0 +1,147 @@
+import pytest
+
+from helpers import brute_margin
+from othello_coach.engine.bitboard import Position, perft, random_position
+from othello_coach.engine.eval import (
+    CORNER_ACCESS, CORNER_OWNED, EDGE_FULL_BONUS, FEAT_CORNERS, FEAT_EMPTIES, FEAT_FRONTIER, FEAT_MOBILITY,
+    FEAT_POT_MOBILITY, POS_CORNER_MOVE, POS_CORNER_THREAT, POS_GAME_OVER, POS_ODD_REGION, best_move_heuristic,
+    corner_eval, dangerous_squares, edge_control, evaluate, evaluate_symmetric, feature_vector, mobility_diff,
+    position_flags, safe_mobility,
+)
+from othello_coach.engine.parity import parity_regions
+from othello_coach.engine.solver import solve_exact
+
+# 6x6 perft from the start, cross-checked against an independent array-based move generator
+PERFT_6X6 = [4, 12, 56, 244, 1364, 7604]
+
+
+@pytest.mark.parametrize("depth", range(1, len(PERFT_6X6) + 1))
+def test_perft_6x6(depth):
+    assert perft(Position.initial(6), depth) == PERFT_6X6[depth - 1]
+
+
+def test_6x6_start_is_on_the_small_board():
+    pos = Position.initial(6)
+    assert pos.legal_squares() == [10, 17, 28, 35]  # C2, B3, E4, D5
+
+
+@pytest.mark.parametrize("seed", range(12))
+def test_6x6_solver_matches_brute_force(seed):
+    pos = random_position(3 + seed % 6, seed, 6)
+    assert solve_exact(pos) == brute_margin(*pos.me_opp(), 6)
+
+
+def test_6x6_parity_regions_stay_on_the_board():
+    pos = random_position(6, 3, 6)
+    regions = parity_regions(pos.black, pos.white, 6)
+    assert sum(r.size for r in regions) == 6
+    assert all(r.size % 2 == r.is_odd for r in regions)
+
+
+POSITIONS_6X6 = [random_position(empties, seed, 6) for empties in (4, 12, 22) for seed in range(4)]
+CORNERS_6X6 = (0, 5, 40, 45)  # A1, F1, A6, F6
+EDGES_6X6 = (
+    [c for c in range(6)], [40 + c for c in range(6)], [8 * r for r in range(6)], [8 * r + 5 for r in range(6)],
+)
+
+
+def _neighbours(sq):
+    r, c = divmod(sq, 8)
+    return [8 * (r + dr) + c + dc for dr in (-1, 0, 1) for dc in (-1, 0, 1)
+            if (dr or dc) and 0 <= r + dr < 6 and 0 <= c + dc < 6]
+
+
+def _squares(bb):
+    return [sq for sq in range(64) if (bb >> sq) & 1]
+
+
+def _replies(pos):
+    # legal squares of the side to move and of the opponent, as if it were their turn
+    return pos.legal_squares(), pos.pass_move().legal_squares()
+
+
+def test_6x6_mobility_ignores_off_board_squares():
+    for pos in POSITIONS_6X6:
+        mine, theirs = _replies(pos)
+        assert mobility_diff(*pos.me_opp(), 6) == len(mine) - len(theirs)
+        assert all(sq % 8 < 6 and sq < 48 for sq in mine + theirs)
+
+
+def test_6x6_safe_mobility_uses_the_small_board_corners():
+    def safe(p):
+        return sum(1 for sq in p.legal_squares()
+                   if not set(p.apply(sq).legal_squares()) & set(CORNERS_6X6))
+
+    for pos in POSITIONS_6X6:
+        assert safe_mobility(pos) == safe(pos) - safe(pos.pass_move())
+
+
+def test_6x6_corner_eval_uses_the_small_board_corners():
+    for pos in POSITIONS_6X6:
+        me, opp = pos.me_opp()
+        held = sum((me >> c) & 1 for c in CORNERS_6X6) - sum((opp >> c) & 1 for c in CORNERS_6X6)
+        access = len(set(_replies(pos)[1]) & set(CORNERS_6X6))
+        assert corner_eval(pos) == CORNER_OWNED * held - CORNER_ACCESS * access
+
+
+def test_6x6_edge_control_uses_the_small_board_edges():
+    for pos in POSITIONS_6X6:
+        me, opp = pos.me_opp()
+        expected = 0
+        for edge in EDGES_6X6:
+            expected += sum((me >> sq) & 1 for sq in edge) - sum((opp >> sq) & 1 for sq in edge)
+            if all((me >> sq) & 1 for sq in edge):
+                expected += EDGE_FULL_BONUS
+            elif all((opp >> sq) & 1 for sq in edge):
+                expected -= EDGE_FULL_BONUS
+        assert edge_control(pos) == expected
+
+
+def test_6x6_position_flags():
+    for pos in POSITIONS_6X6:
+        mine, theirs = _replies(pos)
+        expected = 0
+        if set(mine) & set(CORNERS_6X6):
+            expected |= POS_CORNER_MOVE
+        if set(theirs) & set(CORNERS_6X6):
+            expected |= POS_CORNER_THREAT
+        if any(r.is_odd for r in parity_regions(pos.black, pos.white, 6)):
+            expected |= POS_ODD_REGION
+        if not mine and not theirs:
+            expected |= POS_GAME_OVER
+        assert position_flags(pos) == expected
+
+
+def test_6x6_dangerous_squares_touch_an_empty_small_board_corner():
+    for pos in POSITIONS_6X6:
+        occ = pos.black | pos.white
+        open_corners = [c for c in CORNERS_6X6 if not (occ >> c) & 1]
+        expected = [sq for sq in pos.legal_squares() if any(sq in _neighbours(c) for c in open_corners)]
+        assert dangerous_squares(pos) == expected
+
+
+def test_6x6_feature_vector_counts_on_board_squares():
+    for pos in POSITIONS_6X6:
+        me, opp = pos.me_opp()
+        mine, theirs = _replies(pos)
+        empty = [sq for r in range(6) for sq in range(8 * r, 8 * r + 6) if not ((me | opp) >> sq) & 1]
+
+        def potential(discs):
+            return sum(1 for sq in empty if any(n in discs for n in _neighbours(sq)))
+
+        def frontier(discs):
+            return sum(1 for sq in discs if any(n in empty for n in _neighbours(sq)))
+
+        f = feature_vector(pos)
+        assert f[FEAT_MOBILITY] == len(mine) - len(theirs)
+        assert f[FEAT_POT_MOBILITY] == potential(_squares(opp)) - potential(_squares(me))
+        assert f[FEAT_FRONTIER] == frontier(_squares(me)) - frontier(_squares(opp))
+        assert f[FEAT_CORNERS] == sum((me >> c) & 1 for c in CORNERS_6X6) - sum((opp >> c) & 1 for c in CORNERS_6X6)
+        assert f[FEAT_EMPTIES] == len(empty)
+
+
+def test_6x6_static_evaluation_is_rejected():
+    pos = POSITIONS_6X6[0]
+    for f in (evaluate, evaluate_symmetric, best_move_heuristic):
+        with pytest.raises(ValueError, match="8x8"):
+            f(pos)
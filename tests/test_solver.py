@@ -0,0 +1,384 @@
+import threading
+
+import pytest
+
+from helpers import brute_margin
+from othello_coach.engine.bitboard import CORNER_MASK, KernelError, Position, apply_move, final_margin, random_position
+from othello_coach.engine.search import FLAG_BETA, FLAG_EXACT
+from othello_coach.engine.solver import (
+    CLOCK_CHECK_NODES, DEFAULT_MAX_EMPTIES, DEFAULT_TIME_MS, DISC_SCALE, MAX_SCORE, ExactSolver, SearchCancelled,
+    SearchStats, TTStats, TranspositionTable, best_reply, is_drawn, negamax_search, search_depth, self_play,
+    solve_edax_format, solve_exact, solve_exact_parallel, solve_exact_stats, solve_final_board, solve_iterative,
+    solve_mtdf, solve_multipv, solve_wld,
+)
+
+
+def test_default_cap_rejects_larger_endgames():
+    pos = random_position(DEFAULT_MAX_EMPTIES + 1, 0)
+    with pytest.raises(ValueError, match="exceeds solver cap"):
+        solve_exact(pos)
+
+
+def test_default_cap_is_above_16_with_a_time_budget():
+    assert DEFAULT_MAX_EMPTIES > 16
+    assert ExactSolver().time_ms == DEFAULT_TIME_MS > 0
+    # 20 empties passes the cap, so a tiny budget ends it with a timeout rather than a cap error
+    with pytest.raises(TimeoutError):
+        solve_exact(random_position(20, 0), time_ms=1)
+
+
+def test_cap_is_a_parameter():
+    pos = random_position(10, 0)
+    with pytest.raises(ValueError):
+        ExactSolver(max_empties=9).solve(pos)
+    assert ExactSolver(max_empties=10).solve(pos) == solve_exact(pos)
+
+
+def test_single_pass_then_reply():
+    # White has no move; Black replies and the game goes on to a full board
+    pos = Position(0x81C39D0D2F777901, 0x4A3C62F2D08886FE, 1)
+    assert pos.legal_squares() == [] and pos.pass_move().legal_squares()
+    assert solve_exact(pos) == brute_margin(*pos.me_opp()) == -18
+
+
+@pytest.mark.parametrize("stm, margin", [(0, 60), (1, -60)])
+def test_mid_board_double_pass_scores_the_disc_margin(stm, margin):
+    # A1-C1 against G8-H8: neither side can move with 59 squares empty
+    pos = Position(0b111, (1 << 62) | (1 << 63), stm)
+    assert pos.terminal()
+    assert solve_exact(pos, max_empties=64) == brute_margin(*pos.me_opp()) == margin
+
+
+def test_pass_into_a_blocked_board():
+    # Black must pass; White's only reply, C1, leaves a board neither side can move on
+    pos = Position((1 << 1) | (1 << 63), 1 << 0, 0)
+    assert pos.legal_squares() == [] and pos.pass_move().legal_squares() == [2]
+    assert solve_exact(pos, max_empties=64) == brute_margin(*pos.me_opp()) == -62
+
+
+def test_depth_limited_search_rejects_boards_the_eval_cannot_score():
+    pos = random_position(20, 0, 6)
+    with pytest.raises(ValueError, match="8x8"):
+        search_depth(pos, 2)
+    with pytest.raises(ValueError, match="8x8"):
+        best_reply(pos, 1)
+    with pytest.raises(ValueError, match="8x8"):
+        solve_iterative(pos, 100)
+
+
+def test_self_play_is_reproducible_and_legal():
+    moves = self_play(1, seed=4, margin=200)
+    assert moves == self_play(1, seed=4, margin=200)
+    pos = Position.initial()
+    for sq in moves:
+        pos = apply_move(pos, sq)
+    assert pos.terminal()
+
+
+def test_leaf_and_interior_nodes_add_up_without_tt_cutoffs():
+    solver = ExactSolver(max_empties=64, time_ms=0)
+    me, opp = solver._root(Position.initial())
+    solver._negamax(me, opp, -MAX_SCORE, MAX_SCORE, 1)
+    # the root and its four children, nothing transposes
+    assert (solver.nodes, solver.leaf_nodes, solver.interior_nodes, solver.tt_cutoffs) == (5, 4, 1, 0)
+    solver = ExactSolver(max_empties=64, time_ms=0)
+    me, opp = solver._root(Position.initial())
+    solver._negamax(me, opp, -MAX_SCORE, MAX_SCORE, 3)
+    assert solver.tt_cutoffs == 0
+    assert solver.leaf_nodes + solver.interior_nodes == solver.nodes
+
+
+@pytest.mark.parametrize("seed", range(4))
+def test_every_node_is_a_leaf_an_interior_node_or_a_tt_cutoff(seed):
+    score, stats = solve_exact_stats(random_position(11, seed))
+    assert stats.completed and stats.tt_cutoffs > 0
+    assert stats.leaf_nodes + stats.interior_nodes + stats.tt_cutoffs == stats.nodes
+    solver = ExactSolver(max_empties=64, time_ms=0, quiescence=True)
+    me, opp = solver._root(random_position(30, seed))
+    solver._negamax(me, opp, -MAX_SCORE, MAX_SCORE, 3)
+    assert solver.leaf_nodes + solver.interior_nodes + solver.tt_cutoffs == solver.nodes
+
+
+def test_shared_table_survives_another_solvers_clear():
+    pos = random_position(11, 1)
+    table = TranspositionTable()
+    a, b = ExactSolver(tt=table), ExactSolver(tt=table)
+    score = a.solve(pos)
+    filled, a_stats = len(table.entries()), (a.nodes, a.tt_hits, a.tt_stores)
+    b.clear()
+    assert len(table.entries()) == filled
+    assert (a.nodes, a.tt_hits, a.tt_stores) == a_stats
+    # b starts from a's entries, and only its own traffic shows in its counters
+    assert b.solve(pos) == score
+    assert b.tt_hits > 0 and b.nodes < a.nodes
+    assert (a.nodes, a.tt_hits, a.tt_stores) == a_stats
+    assert table.stats.hits == a.tt_hits + b.tt_hits
+
+
+def test_two_threads_share_one_table():
+    pos = random_position(12, 5)
+    child = apply_move(pos, pos.legal_squares()[0])
+    table = TranspositionTable()
+    solvers = [ExactSolver(tt=table), ExactSolver(tt=table)]
+    results = [None, None]
+
+    def run(i, p):
+        results[i] = solvers[i].solve(p)
+
+    threads = [threading.Thread(target=run, args=(0, pos)), threading.Thread(target=run, args=(1, child))]
+    for t in threads:
+        t.start()
+    for t in threads:
+        t.join()
+    assert results == [solve_exact(pos), solve_exact(child)]
+    assert all(s.tt_hits > 0 for s in solvers)
+
+
+@pytest.mark.parametrize("n", [1, 3, 30])
+def test_multipv_lists_the_best_lines_first(n):
+    pos = random_position(10, 2)
+    lines = solve_multipv(pos, n)
+    assert len(lines) == min(n, len(pos.legal_squares()))
+    assert lines[0][1] == solve_exact(pos)
+    assert [score for _, score in lines] == sorted((score for _, score in lines), reverse=True)
+    for sq, score in lines:
+        child = apply_move(pos, sq)
+        assert score == (solve_exact(child) if child.stm == pos.stm else -solve_exact(child))
+
+
+@pytest.mark.parametrize("n", [0, -1])
+def test_multipv_rejects_fewer_than_one_line(n):
+    with pytest.raises(KernelError):
+        solve_multipv(random_position(10, 2), n)
+
+
+SAMPLE = [random_position(empties, seed) for empties in (6, 9, 12) for seed in range(6)]
+DRAWN = [random_position(6, 12), random_position(8, 42), random_position(10, 48)]
+
+
+def test_wld_sign_matches_the_exact_margin():
+    for pos in SAMPLE + DRAWN:
+        margin = solve_exact(pos)
+        assert solve_wld(pos) == (margin > 0) - (margin < 0)
+    assert [solve_wld(pos) for pos in DRAWN] == [0, 0, 0]
+
+
+def test_second_solve_reuses_the_table():
+    pos = random_position(12, 3)
+    solver = ExactSolver()
+    score = solver.solve(pos)
+    first_nodes, first_hits = solver.nodes, solver.tt_hits
+    assert solver.tt_stores > 0 and solver.tt.stats.stores == solver.tt_stores
+    assert solver.solve(pos) == score
+    assert solver.tt_hits > first_hits
+    assert solver.nodes - first_nodes < first_nodes // 10
+
+
+def test_depth_preferred_slot_keeps_the_deeper_entry():
+    table = TranspositionTable(tt_mb=0)  # the minimum: one bucket, so every position collides
+    assert table.buckets == 1
+    table.store(1, 2, 5, 100, FLAG_EXACT)
+    table.store(4, 8, 3, 200, FLAG_EXACT)  # too shallow for the first slot, takes the second
+    assert table.probe(1, 2)[3] == 100 and table.probe(4, 8)[3] == 200
+    table.store(16, 32, 4, 300, FLAG_EXACT)  # still shallower, evicts the always-replace slot
+    assert table.probe(1, 2) is not None and table.probe(4, 8) is None
+    table.store(64, 128, 5, 400, FLAG_EXACT)  # as deep as the first slot, so it takes it
+    assert table.probe(1, 2) is None and table.probe(64, 128)[3] == 400
+    table.store(64, 128, 1, 500, FLAG_BETA)  # the same position always updates in place
+    assert table.probe(64, 128)[2:5] == (1, 500, FLAG_BETA)
+    assert table.stats == TTStats(probes=7, hits=5, stores=5, collisions=2)
+
+
+def test_time_budget_raises_instead_of_scoring():
+    pos = random_position(14, 0)
+    with pytest.raises(TimeoutError, match="exceeded 1 ms"):
+        solve_exact(pos, time_ms=1)
+    score, stats = solve_exact_stats(pos, time_ms=1)
+    assert score is None and not stats.completed
+    # the clock is read every CLOCK_CHECK_NODES nodes, so the abort comes at the first check
+    assert stats.nodes == CLOCK_CHECK_NODES
+
+
+def test_mtdf_matches_the_aspiration_solve():
+    for pos in SAMPLE + DRAWN:
+        assert solve_mtdf(pos) == solve_exact(pos)
+
+
+@pytest.mark.parametrize("seed", range(3))
+def test_root_split_across_processes_matches_the_serial_solve(seed):
+    pos = random_position(10, seed)
+    assert len(pos.legal_squares()) >= 2
+    assert solve_exact_parallel(pos, workers=2) == solve_exact(pos)
+
+
+def test_full_depth_search_is_the_exact_solve():
+    # the 12-empties positions are slow to search with the full window
+    for pos in SAMPLE[:12] + DRAWN[:2]:
+        empties = 64 - bin(pos.black | pos.white).count("1")
+        score, best = search_depth(pos, empties)
+        assert score == solve_exact(pos) * DISC_SCALE
+        if pos.legal_squares():
+            child = apply_move(pos, best)
+            assert (solve_exact(child) if child.stm == pos.stm else -solve_exact(child)) * DISC_SCALE == score
+
+
+def test_window_is_exact_inside_and_a_bound_outside():
+    for pos in SAMPLE[:12] + DRAWN:
+        exact = solve_exact(pos)
+        assert negamax_search(pos, exact - 1, exact + 1) == exact
+        assert negamax_search(pos, -64, 64) == exact
+        assert negamax_search(pos, exact, 64) <= exact     # fails low
+        assert negamax_search(pos, -64, exact) >= exact    # fails high
+        # one solver, several windows: the TT keeps each entry's bound type
+        solver = ExactSolver()
+        assert solver.window(pos, exact, 64) <= exact
+        assert solver.window(pos, -64, exact) >= exact
+        assert solver.window(pos, -64, 64) == exact
+
+
+@pytest.mark.parametrize("alpha, beta", [(3, 3), (5, -5), (-65, 0), (0, 65)])
+def test_window_rejects_an_empty_or_out_of_range_window(alpha, beta):
+    with pytest.raises(ValueError, match="Window must satisfy"):
+        negamax_search(random_position(6, 0), alpha, beta)
+
+
+def test_stability_cutoff_keeps_the_scores_and_saves_nodes():
+    plain_nodes = cut_nodes = 0
+    for pos in SAMPLE + DRAWN:
+        plain, cut = ExactSolver(), ExactSolver(stability_cutoff=True)
+        assert cut.solve(pos) == plain.solve(pos)
+        assert cut.window(pos, -64, 64) == plain.solve(pos)
+        plain_nodes += plain.nodes
+        cut_nodes += cut.nodes
+    assert cut_nodes < plain_nodes
+
+
+def test_progress_callback_cancels_the_solve():
+    calls = []
+
+    def progress(nodes, elapsed_ms):
+        calls.append(nodes)
+        return len(calls) < 3
+
+    pos = random_position(14, 0)
+    with pytest.raises(SearchCancelled, match="cancelled after"):
+        solve_exact(pos, progress=progress)
+    assert calls == [CLOCK_CHECK_NODES, 2 * CLOCK_CHECK_NODES, 3 * CLOCK_CHECK_NODES]
+    score, stats = solve_exact_stats(pos, progress=lambda nodes, elapsed_ms: False)
+    assert score is None and not stats.completed and stats.nodes == CLOCK_CHECK_NODES
+    # a callback that always agrees changes nothing
+    assert solve_exact(SAMPLE[3], progress=lambda nodes, elapsed_ms: True) == solve_exact(SAMPLE[3])
+
+
+def test_root_report_scores_every_move_and_accounts_for_every_node():
+    for pos in SAMPLE[:12]:
+        solver = ExactSolver()
+        report = solver.root_report(pos)
+        assert sorted(sq for sq, _, _ in report) == pos.legal_squares()
+        assert sum(nodes for _, _, nodes in report) + 1 == solver.nodes
+        for sq, margin, _ in report:
+            child = apply_move(pos, sq)
+            assert margin == (solve_exact(child) if child.stm == pos.stm else -solve_exact(child))
+        assert max(margin for _, margin, _ in report) == solve_exact(pos)
+
+
+def test_final_board_reaches_the_solved_margin():
+    for pos in SAMPLE[:12] + DRAWN + [random_position(8, 1, 6)]:
+        final = solve_final_board(pos, max_empties=64)
+        assert final.terminal()
+        # play only adds and flips discs
+        assert (pos.black | pos.white) & ~(final.black | final.white) == 0
+        me, opp = (final.black, final.white) if pos.stm == 0 else (final.white, final.black)
+        assert final_margin(me, opp, pos.size) == solve_exact(pos)
+
+
+def test_dict_backed_database_answers_the_root_and_its_children():
+    pos = SAMPLE[10]
+    db = {}
+    score = solve_exact(pos, db_store=db.__setitem__)
+    assert db == {pos.hash64(): score}
+    solver = ExactSolver(db_probe=db.get)
+    assert solver.solve(pos) == score and solver.nodes == 0
+    # with only the children known, the root search stops one ply down
+    db = {}
+    for sq in pos.legal_squares():
+        solve_exact(apply_move(pos, sq), db_store=db.__setitem__)
+    plain, backed = ExactSolver(), ExactSolver(db_probe=db.get, db_store=db.__setitem__)
+    assert backed.solve(pos) == plain.solve(pos) == score and db[pos.hash64()] == score
+    assert backed.nodes <= 1 + 2 * len(pos.legal_squares()) < plain.nodes
+
+
+def test_dump_tt_holds_the_exact_root_entry():
+    pos = next(p for p in SAMPLE if p.stm == 0 and p.legal_squares())
+    solver = ExactSolver()
+    score = solver.solve(pos)
+    dump = solver.dump_tt()
+    assert len(dump) == len(solver.tt.entries())
+    root = [entry for entry in dump if entry[0] == pos.hash64()]
+    empties = 64 - bin(pos.black | pos.white).count("1")
+    assert root == [(pos.hash64(), score * DISC_SCALE, empties, FLAG_EXACT)]
+
+
+def test_stats_match_the_solver_they_came_from():
+    pos = random_position(11, 0)
+    score, stats = solve_exact_stats(pos)
+    solver = ExactSolver()
+    assert score == solver.solve(pos)
+    assert stats == SearchStats(
+        nodes=solver.nodes, leaf_nodes=solver.leaf_nodes, interior_nodes=solver.interior_nodes,
+        cutoff_nodes=solver.cutoff_nodes, tt_cutoffs=solver.tt_cutoffs, tt_hits=solver.tt_hits,
+        tt_stores=solver.tt_stores, elapsed_ms=stats.elapsed_ms, completed=True,
+    )
+    assert 0 < stats.cutoff_nodes < stats.interior_nodes
+    assert stats.tt_cutoffs <= stats.tt_hits and stats.tt_stores <= stats.interior_nodes
+    assert stats.elapsed_ms >= 0
+
+
+def test_best_reply_is_legal_and_perfect_at_full_depth():
+    for pos in SAMPLE[:12]:
+        empties = 64 - bin(pos.black | pos.white).count("1")
+        sq = best_reply(pos, empties)
+        child = apply_move(pos, sq)
+        assert (solve_exact(child) if child.stm == pos.stm else -solve_exact(child)) == solve_exact(pos)
+        for depth in (0, 2):
+            assert pos.is_legal(best_reply(pos, depth))
+    # White holds both corners of rank 1, so Black must pass
+    assert best_reply(Position((1 << 1) | (1 << 6), (1 << 0) | (1 << 7), 0)) is None
+    with pytest.raises(ValueError, match="non-negative"):
+        best_reply(Position.initial(), -1)
+
+
+def test_quiescence_only_adds_corner_captures():
+    # White to move: H8 takes back the whole G7-E5 diagonal and wipes Black out
+    pos = Position((1 << 36) | (1 << 45) | (1 << 54), 1 << 27, 1)
+    assert search_depth(pos, 0, quiescence=True)[0] == MAX_SCORE > search_depth(pos, 0)[0]
+    for seed in range(6):
+        pos = random_position(30, seed)
+        plain, quiet = search_depth(pos, 0)[0], search_depth(pos, 0, quiescence=True)[0]
+        if pos.legal_mask() & CORNER_MASK:
+            assert quiet >= plain
+        else:
+            assert quiet == plain
+    for pos in SAMPLE[:6]:
+        assert search_depth(pos, 6, quiescence=True)[0] == solve_exact(pos) * DISC_SCALE
+
+
+def test_is_drawn_only_for_drawn_endgames():
+    assert all(is_drawn(pos) for pos in DRAWN)
+    for pos in SAMPLE[:12]:
+        assert is_drawn(pos) == (solve_exact(pos) == 0)
+    # A1-C1 against G8-H8 is over and lost by 60
+    assert not is_drawn(Position(0b111, 3 << 62, 1), max_empties=64)
+    with pytest.raises(TimeoutError):
+        is_drawn(random_position(14, 0), time_ms=1)
+
+
+def test_edax_format_scores():
+    for pos in SAMPLE[:12] + DRAWN:
+        assert solve_edax_format(pos) == solve_exact(pos)
+    # finished games well past the empties cap: the empties go to the winner
+    assert solve_edax_format(Position(0b111, 3 << 62, 0)) == 60
+    assert solve_edax_format(Position(0b111, 3 << 62, 1)) == -60
+    assert solve_edax_format(Position(0x3C3C00000000, 0, 1)) == -64
+    with pytest.raises(ValueError, match="8x8"):
+        solve_edax_format(random_position(6, 0, 6))
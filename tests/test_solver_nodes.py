@@ -0,0 +1,129 @@
+"""Node counts on a fixed endgame suite, so ordering and pruning changes can be checked in review.
+
+Each test compares the solver against itself with one technique taken away; the
+scores must not change and the node total must move the way the technique claims.
+The pinned total is a plain regression guard: update it on purpose, quoting the
+old and new numbers in the commit message.
+"""
+from functools import lru_cache
+
+from othello_coach.engine import solver
+from othello_coach.engine.bitboard import all_flips, final_margin, legal_moves, popcount, random_position
+from othello_coach.engine.search import FLAG_ALPHA, FLAG_BETA, FLAG_EXACT
+from othello_coach.engine.solver import DISC_SCALE, FULL_DEPTH, MAX_SCORE, ExactSolver
+
+ENDGAMES = [random_position(11, seed) for seed in range(8)]
+ENDGAME_SCORES = [42, 20, -18, -4, -28, 14, -22, 24]
+ENDGAME_NODES = 62012
+
+# Parity ordering only starts to pay off around 14 empties
+PARITY_ENDGAMES = [random_position(14, seed) for seed in (1, 2)]
+
+
+class UnorderedSolver(ExactSolver):
+    """Tries moves in square order: no corners first, no mobility sort."""
+
+    def _order_moves(self, me, opp, empties):
+        return [(1 << sq, flips) for sq, flips in all_flips(me, opp, self.size)]
+
+
+class AlphaBetaSolver(ExactSolver):
+    """ExactSolver._negamax with a full window for every child instead of PVS, and no ETC."""
+
+    def _negamax(self, me, opp, alpha, beta, depth=FULL_DEPTH):
+        self.nodes += 1
+        if not me or not opp:
+            return final_margin(me, opp, self.size) * DISC_SCALE
+        empties = self.squares - popcount(me | opp)
+        draft = min(depth, empties)
+        entry = self.tt.probe(me, opp)
+        if entry is not None and entry[2] >= draft:
+            ts, tf = entry[3], entry[4]
+            if tf == FLAG_EXACT or (tf == FLAG_BETA and ts >= beta) or (tf == FLAG_ALPHA and ts <= alpha):
+                return ts
+        ordered = self._order_moves(me, opp, empties)
+        if not ordered:
+            if not legal_moves(opp, me, self.size):
+                return final_margin(me, opp, self.size) * DISC_SCALE
+            return -self._negamax(opp, me, -beta, -alpha, depth)
+        orig_alpha = alpha
+        best, best_move = -MAX_SCORE - 1, 0
+        for move, flips in ordered:
+            s = -self._negamax(opp ^ flips, me | flips | move, -beta, -alpha, depth - 1)
+            if s > best:
+                best, best_move = s, move
+                alpha = max(alpha, s)
+                if alpha >= beta:
+                    break
+        flag = FLAG_EXACT
+        if best <= orig_alpha:
+            flag = FLAG_ALPHA
+        elif best >= beta:
+            flag = FLAG_BETA
+        self.tt.store(me, opp, draft, best, flag, best_move.bit_length() - 1)
+        return best
+
+
+def _run(positions, make=ExactSolver, full_window=False):
+    scores, nodes = [], 0
+    for pos in positions:
+        s = make()
+        if full_window:
+            me, opp = s._root(pos)
+            scores.append(s._negamax(me, opp, -MAX_SCORE, MAX_SCORE) // DISC_SCALE)
+        else:
+            scores.append(s.solve(pos))
+        nodes += s.nodes
+    return scores, nodes
+
+
+@lru_cache(maxsize=None)
+def _baseline():
+    return _run(ENDGAMES)
+
+
+def test_suite_scores_and_nodes_are_pinned():
+    assert _baseline() == (ENDGAME_SCORES, ENDGAME_NODES)
+
+
+def test_move_ordering_halves_the_nodes():
+    scores, nodes = _run(ENDGAMES, UnorderedSolver)
+    assert scores == ENDGAME_SCORES
+    assert 2 * _baseline()[1] < nodes
+
+
+def test_pvs_beats_full_window_alpha_beta(monkeypatch):
+    # both without ETC and aspiration, so only the child windows differ
+    monkeypatch.setattr(solver, "ETC_MIN_EMPTIES", 65)
+    pvs_scores, pvs_nodes = _run(ENDGAMES, full_window=True)
+    ab_scores, ab_nodes = _run(ENDGAMES, AlphaBetaSolver, full_window=True)
+    assert pvs_scores == ab_scores == ENDGAME_SCORES
+    assert pvs_nodes < 0.9 * ab_nodes
+
+
+def test_aspiration_window_beats_full_window():
+    scores, nodes = _run(ENDGAMES, full_window=True)
+    assert scores == ENDGAME_SCORES
+    assert _baseline()[1] < 0.95 * nodes
+
+
+def test_etc_saves_nodes(monkeypatch):
+    monkeypatch.setattr(solver, "ETC_MIN_EMPTIES", 65)
+    scores, nodes = _run(ENDGAMES)
+    assert scores == ENDGAME_SCORES
+    assert _baseline()[1] < nodes
+
+
+def test_killers_and_history_are_node_neutral():
+    # measured: within a few percent of mobility-only ordering, which is why they're opt-in
+    scores, nodes = _run(ENDGAMES, lambda: ExactSolver(cutoff_heuristics=True))
+    assert scores == ENDGAME_SCORES
+    assert abs(nodes - _baseline()[1]) < 0.05 * _baseline()[1]
+
+
+def test_parity_ordering_saves_nodes(monkeypatch):
+    scores, nodes = _run(PARITY_ENDGAMES)
+    monkeypatch.setattr(solver, "PARITY_BONUS", 0)
+    plain_scores, plain_nodes = _run(PARITY_ENDGAMES)
+    assert scores == plain_scores
+    assert nodes < 0.95 * plain_nodes
@@ -0,0 +1,89 @@
+import pytest
+
+from helpers import ever_flipped
+from othello_coach.engine.bitboard import Position, random_position
+from othello_coach.engine.stability import (
+    EDGE_TABLE, edge_stable_discs, stability_diff, stability_full, stable_count, stable_discs, stable_mask,
+)
+
+# (size, empties): small enough for ever_flipped to search every line of play
+SMALL_ENDGAMES = [random_position(empties, seed, size) for size, empties in ((8, 6), (8, 7), (6, 8))
+                  for seed in range(8)]
+
+
+def test_edge_table_entries():
+    # own << 8 | opp for rank 1, bit 0 = A1
+    assert EDGE_TABLE[0xFF << 8] == 0xFF           # a full edge of one colour
+    assert EDGE_TABLE[0x01 << 8] == 0x01           # a lone corner
+    assert EDGE_TABLE[0x02 << 8] == 0              # B1 with A1 still open
+    assert EDGE_TABLE[0x07 << 8 | 0x08] == 0x07    # A1-C1 against a D1 that could flank nothing
+    assert EDGE_TABLE[0x0F << 8 | 0xF0] == 0x0F    # a full edge split between the colours
+
+
+@pytest.mark.parametrize("pos", SMALL_ENDGAMES)
+def test_edge_stable_discs_are_never_flipped(pos):
+    flipped = ever_flipped(pos.black, pos.white, pos.size)
+    for own, opp in ((pos.black, pos.white), (pos.white, pos.black)):
+        stable = edge_stable_discs(own, opp, pos.size)
+        assert stable & ~own == 0
+        assert stable & flipped == 0
+
+
+@pytest.mark.parametrize("pos", SMALL_ENDGAMES)
+def test_stable_discs_are_never_flipped(pos):
+    flipped = ever_flipped(pos.black, pos.white, pos.size)
+    for own, opp in ((pos.black, pos.white), (pos.white, pos.black)):
+        stable = stable_discs(own, opp, pos.size)
+        assert stable & ~own == 0
+        assert stable & flipped == 0
+        # the full-board pass finds everything the edge table does, and more
+        assert edge_stable_discs(own, opp, pos.size) & ~stable == 0
+
+
+def test_stability_finds_most_unflippable_discs():
+    found = actual = 0
+    for pos in SMALL_ENDGAMES:
+        flipped = ever_flipped(pos.black, pos.white, pos.size)
+        found += sum(stable_count(own, opp, pos.size) for own, opp in ((pos.black, pos.white), (pos.white, pos.black)))
+        actual += bin((pos.black | pos.white) & ~flipped).count("1")
+    # a sound definition can only miss discs; measured 0.80 on this suite
+    assert 0.75 * actual < found <= actual
+
+
+@pytest.mark.parametrize("size", [6, 8])
+def test_full_board_is_entirely_stable(size):
+    pos = random_position(0, 1, size)
+    assert stability_full(pos.black, pos.white, size) == (bin(pos.black).count("1"), bin(pos.white).count("1"))
+
+
+@pytest.mark.parametrize("pos", SMALL_ENDGAMES)
+def test_stable_mask_marks_unflippable_discs_of_each_colour(pos):
+    black, white = stable_mask(pos.black, pos.white, pos.size)
+    assert black & ~pos.black == 0 and white & ~pos.white == 0
+    assert (black | white) & ever_flipped(pos.black, pos.white, pos.size) == 0
+
+
+def test_disc_on_four_full_lines_is_counted():
+    # D4 black, every square on its rank, file and both diagonals white: nothing can flank D4
+    lines = 0
+    for sq in range(64):
+        row, col = divmod(sq, 8)
+        if row == 3 or col == 3 or row - col == 0 or row + col == 6:
+            lines |= 1 << sq
+    d4 = 1 << 27
+    black, white = d4, lines & ~d4
+    assert stable_mask(black, white)[0] == d4
+    assert stability_full(black, white) == (1, stable_count(white, black))
+    # with A7 empty the G1-A7 diagonal is open and D4 no longer counts
+    assert stability_full(black, white & ~(1 << 48))[0] == 0
+
+
+def test_stability_diff_is_from_the_side_to_move():
+    # Black's full rank 1 is stable; White's lone D4 is not
+    pos = Position(0xFF, 1 << 27, 0)
+    assert stability_diff(pos) == 8
+    assert stability_diff(pos.pass_move()) == -8
+    assert stability_diff(Position.initial()) == 0
+    for pos in SMALL_ENDGAMES:
+        black, white = stability_full(pos.black, pos.white, pos.size)
+        assert stability_diff(pos) == (black - white if pos.stm == 0 else white - black)
@@ -0,0 +1,63 @@
+import pytest
+
+from othello_coach.engine.bitboard import BOARD_MASKS, Position, apply_move, perft, random_position
+from othello_coach.engine.symmetry import INVERSE, are_equivalent, canonical_form, transform, transform_square
+
+
+def _square(sq, k, size):
+    # reference transform on (file, rank) coordinates within the size x size frame
+    f, r = sq % 8, sq // 8
+    for _ in range(k & 3):
+        f, r = size - 1 - r, f
+    if k & 4:
+        r = size - 1 - r
+    return r * 8 + f
+
+
+def _transformed(pos, k):
+    return Position(transform(pos.black, k, pos.size), transform(pos.white, k, pos.size), pos.stm, pos.size)
+
+
+@pytest.mark.parametrize("size", [6, 8])
+def test_transforms_match_coordinate_reference(size):
+    for k in range(8):
+        for r in range(size):
+            for f in range(size):
+                sq = r * 8 + f
+                assert transform_square(sq, k, size) == _square(sq, k, size)
+
+
+@pytest.mark.parametrize("size", [6, 8])
+def test_transforms_stay_on_the_board_and_invert(size):
+    mask = BOARD_MASKS[size]
+    for k in range(8):
+        assert transform(mask, k, size) == mask
+        for seed in range(5):
+            pos = random_position(10, seed, size)
+            assert transform(transform(pos.black, k, size), INVERSE[k], size) == pos.black
+
+
+@pytest.mark.parametrize("size", [6, 8])
+def test_every_transform_is_equivalent(size):
+    for seed in range(5):
+        pos = random_position(size * size // 3, seed, size)
+        canon, k = canonical_form(pos)
+        assert canon.size == size
+        assert canon == _transformed(pos, k)
+        for j in range(8):
+            image = _transformed(pos, j)
+            assert are_equivalent(pos, image)
+            assert canonical_form(image)[0] == canon
+        assert perft(canon, 2) == perft(pos, 2)
+
+
+@pytest.mark.parametrize("size", [6, 8])
+def test_distinct_positions_are_not_equivalent(size):
+    start = Position.initial(size)
+    first = start.legal_squares()[0]
+    a = apply_move(start, first)
+    # a single move is the same up to symmetry whichever one is played, a second disc is not
+    b = apply_move(a, a.legal_squares()[0])
+    assert all(are_equivalent(a, apply_move(start, sq)) for sq in start.legal_squares())
+    assert not are_equivalent(a, b)
+    assert not are_equivalent(a, Position(a.black, a.white, 1 - a.stm, size))